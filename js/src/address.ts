@@ -29,3 +29,19 @@ export function findMultisigTransactionAddress(
   );
   return addr;
 }
+
+export function findVaultAddress(
+  multisigWallet: anchor.web3.PublicKey,
+  index: number,
+  program: anchor.web3.PublicKey,
+): anchor.web3.PublicKey {
+  let [addr, _] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("Vault"),
+      multisigWallet.toBuffer(),
+      Buffer.from([index]),
+    ],
+    program,
+  );
+  return addr;
+}
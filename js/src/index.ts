@@ -1,2 +1,3 @@
-export { findMultisigWalletAddress, findMultisigTransactionAddress } from "./address";
-export { MultisigMember, newMultisigRpc } from "./multisig_member";
\ No newline at end of file
+export { findMultisigWalletAddress, findMultisigTransactionAddress, findVaultAddress } from "./address";
+export { MultisigMember, newMultisigRpc, sortMembers, toMember } from "./multisig_member";
+export type { Member, MemberSpec } from "./multisig_member";
\ No newline at end of file
@@ -48,7 +48,9 @@ export type MultisigDemo = {
         {
           "name": "members",
           "type": {
-            "vec": "publicKey"
+            "vec": {
+              "defined": "Member"
+            }
           }
         }
       ]
@@ -56,7 +58,10 @@ export type MultisigDemo = {
     {
       "name": "newTransaction",
       "docs": [
-        "Initialize a new [MultisigTransaction]."
+        "Initialize a new [MultisigTransaction]. Upon execution, the [state::Vault]",
+        "at `vault_index` signs alongside the [MultisigWallet].",
+        "The transaction expires `ttl` seconds after creation, or never if `ttl`",
+        "is zero. [None] falls back on the wallet's `default_ttl`."
       ],
       "accounts": [
         {
@@ -95,6 +100,16 @@ export type MultisigDemo = {
               "defined": "Instruction"
             }
           }
+        },
+        {
+          "name": "vaultIndex",
+          "type": "u8"
+        },
+        {
+          "name": "ttl",
+          "type": {
+            "option": "u32"
+          }
         }
       ]
     },
@@ -146,6 +161,31 @@ export type MultisigDemo = {
       ],
       "args": []
     },
+    {
+      "name": "reject",
+      "docs": [
+        "Reject a [MultisigTransaction]. Once enough members reject it that the",
+        "threshold can no longer be reached, the transaction is cancelled."
+      ],
+      "accounts": [
+        {
+          "name": "member",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "multisigWallet",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "transaction",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
     {
       "name": "execute",
       "docs": [
@@ -178,6 +218,22 @@ export type MultisigDemo = {
           "name": "multisigWallet",
           "isMut": true,
           "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
@@ -187,6 +243,70 @@ export type MultisigDemo = {
         }
       ]
     },
+    {
+      "name": "changeMinDelay",
+      "accounts": [
+        {
+          "name": "multisigWallet",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "minDelay",
+          "type": "u32"
+        }
+      ]
+    },
+    {
+      "name": "changeDefaultTtl",
+      "accounts": [
+        {
+          "name": "multisigWallet",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "defaultTtl",
+          "type": "u32"
+        }
+      ]
+    },
     {
       "name": "changeMembers",
       "accounts": [
@@ -194,16 +314,59 @@ export type MultisigDemo = {
           "name": "multisigWallet",
           "isMut": true,
           "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
           "name": "members",
           "type": {
-            "vec": "publicKey"
+            "vec": {
+              "defined": "Member"
+            }
           }
         }
       ]
+    },
+    {
+      "name": "closeTransaction",
+      "docs": [
+        "Close a [MultisigTransaction] that was executed, cancelled, expired, or that",
+        "belongs to a previous wallet configuration, and return its rent to the proposer."
+      ],
+      "accounts": [
+        {
+          "name": "multisigWallet",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "transaction",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "proposer",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
     }
   ],
   "accounts": [
@@ -226,20 +389,38 @@ export type MultisigDemo = {
             "name": "members",
             "docs": [
               "Members able to create and approve a [MultisigTransaction] owned by this",
-              "multisig."
+              "multisig, sorted by their key."
             ],
             "type": {
-              "vec": "publicKey"
+              "vec": {
+                "defined": "Member"
+              }
             }
           },
           {
             "name": "threshold",
             "docs": [
-              "At least this many members needed to approve [MultisigTransaction]",
-              "before it can be executed."
+              "At least this much total [Member.weight] needs to approve a",
+              "[MultisigTransaction] before it can be executed."
             ],
             "type": "u16"
           },
+          {
+            "name": "minDelay",
+            "docs": [
+              "Minimum number of seconds between a [MultisigTransaction] first reaching",
+              "the threshold and its execution. Only changeable through [Administration]."
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "defaultTtl",
+            "docs": [
+              "Seconds until a new [MultisigTransaction] expires, unless the proposer",
+              "specifies otherwise. Zero means that transactions don't expire by default."
+            ],
+            "type": "u32"
+          },
           {
             "name": "txNonce",
             "docs": [
@@ -248,11 +429,13 @@ export type MultisigDemo = {
             "type": "u64"
           },
           {
-            "name": "memberSetSeqno",
+            "name": "configSeqno",
             "docs": [
-              "This is used to make sure that a transaction created with owner set X",
-              "cannot be approved by a new owner set Y. This handles the edge cases",
-              "where a user approves a transaction and then leaves multisig membership."
+              "Incremented by every [Administration] change, so that a transaction",
+              "created under configuration X cannot be approved or executed under a",
+              "new configuration Y. This handles the edge cases where a user approves",
+              "a transaction and then leaves multisig membership, or where the",
+              "threshold or timelock change while a transaction is pending."
             ],
             "type": "u32"
           },
@@ -282,6 +465,13 @@ export type MultisigDemo = {
               }
             }
           },
+          {
+            "name": "vaultIndex",
+            "docs": [
+              "The [Vault] that signs alongside the [MultisigWallet] during execution."
+            ],
+            "type": "u8"
+          },
           {
             "name": "multisigWallet",
             "docs": [
@@ -303,9 +493,22 @@ export type MultisigDemo = {
             }
           },
           {
-            "name": "memberSetSeqno",
+            "name": "rejected",
+            "docs": [
+              "Keeps track of which accounts rejected the transaction, and when.",
+              "`rejected[i]` is Some iff `[MultisigWallet.members[i]]` rejected the transaction.",
+              "A member cannot have both approved and rejected the same transaction."
+            ],
+            "type": {
+              "vec": {
+                "option": "i64"
+              }
+            }
+          },
+          {
+            "name": "configSeqno",
             "docs": [
-              "Saved to ensure that no approvals occur on a different set of memberships."
+              "Saved to ensure that no approvals occur under a different wallet configuration."
             ],
             "type": "u32"
           },
@@ -316,6 +519,16 @@ export type MultisigDemo = {
             ],
             "type": "i64"
           },
+          {
+            "name": "expiresAt",
+            "docs": [
+              "Unix timestamp after which the transaction can no longer be approved or executed,",
+              "or [None] if it doesn't expire."
+            ],
+            "type": {
+              "option": "i64"
+            }
+          },
           {
             "name": "proposer",
             "docs": [
@@ -332,6 +545,16 @@ export type MultisigDemo = {
               "option": "publicKey"
             }
           },
+          {
+            "name": "thresholdReachedAt",
+            "docs": [
+              "When the transaction reached the approval threshold, which starts the",
+              "[MultisigWallet.min_delay] timelock. Cleared when approvals fall below it."
+            ],
+            "type": {
+              "option": "i64"
+            }
+          },
           {
             "name": "executedAt",
             "docs": [
@@ -340,12 +563,42 @@ export type MultisigDemo = {
             "type": {
               "option": "i64"
             }
+          },
+          {
+            "name": "cancelledAt",
+            "docs": [
+              "If/when enough members rejected the transaction that the threshold",
+              "can no longer be reached."
+            ],
+            "type": {
+              "option": "i64"
+            }
           }
         ]
       }
     }
   ],
   "types": [
+    {
+      "name": "Member",
+      "docs": [
+        "A member of a [MultisigWallet], whose approval counts `weight` towards",
+        "the wallet's threshold."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "key",
+            "type": "publicKey"
+          },
+          {
+            "name": "weight",
+            "type": "u16"
+          }
+        ]
+      }
+    },
     {
       "name": "Instruction",
       "docs": [
@@ -418,34 +671,321 @@ export type MultisigDemo = {
       }
     }
   ],
-  "errors": [
-    {
-      "code": 6000,
-      "name": "InvalidThreshold",
-      "msg": "Threshold must be <= the number of multisig wallet members"
-    },
-    {
-      "code": 6001,
-      "name": "DuplicateMembers",
-      "msg": "Members of a multisig must be unique addresses"
-    },
+  "events": [
     {
-      "code": 6002,
-      "name": "TooFewMembers",
-      "msg": "Not enough members with the given threshold"
+      "name": "MultisigCreated",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "base",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "members",
+          "type": {
+            "vec": {
+              "defined": "Member"
+            }
+          },
+          "index": false
+        },
+        {
+          "name": "threshold",
+          "type": "u16",
+          "index": false
+        }
+      ]
     },
     {
-      "code": 6003,
-      "name": "NotAMember",
-      "msg": "Not a current member of the multisig wallet"
+      "name": "TransactionProposed",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "vaultIndex",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "expiresAt",
+          "type": {
+            "option": "i64"
+          },
+          "index": false
+        }
+      ]
     },
     {
-      "code": 6004,
-      "name": "InvalidMemberSetSeqno",
-      "msg": "The multisig wallet does not match the transaction's member_set_seqno"
+      "name": "TransactionApproved",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "member",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "approvedWeight",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
     },
     {
-      "code": 6005,
+      "name": "TransactionUnapproved",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "member",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "approvedWeight",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionRejected",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "member",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "cancelled",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionExecuted",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "executor",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionClosed",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposer",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "ThresholdChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "threshold",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "MinDelayChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "minDelay",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "DefaultTtlChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "defaultTtl",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "MembersChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "members",
+          "type": {
+            "vec": {
+              "defined": "Member"
+            }
+          },
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "InvalidThreshold",
+      "msg": "Threshold must be between 1 and the total weight of the multisig wallet members"
+    },
+    {
+      "code": 6001,
+      "name": "DuplicateMembers",
+      "msg": "Members of a multisig must be unique addresses"
+    },
+    {
+      "code": 6002,
+      "name": "TooFewMembers",
+      "msg": "Not enough member weight to reach the given threshold"
+    },
+    {
+      "code": 6003,
+      "name": "NotAMember",
+      "msg": "Not a current member of the multisig wallet"
+    },
+    {
+      "code": 6004,
+      "name": "InvalidConfigSeqno",
+      "msg": "The multisig wallet's configuration changed since the transaction was proposed"
+    },
+    {
+      "code": 6005,
       "name": "InvalidMultisigReference",
       "msg": "The transaction does not belong to the provided multisig"
     },
@@ -468,6 +1008,36 @@ export type MultisigDemo = {
       "code": 6009,
       "name": "AlreadyExecuted",
       "msg": "Transaction already executed"
+    },
+    {
+      "code": 6010,
+      "name": "NotCloseable",
+      "msg": "Only executed, cancelled or expired transactions, or those from a previous wallet configuration, can be closed"
+    },
+    {
+      "code": 6011,
+      "name": "AlreadyRejected",
+      "msg": "Signer already rejected this transaction"
+    },
+    {
+      "code": 6012,
+      "name": "AlreadyCancelled",
+      "msg": "Transaction was cancelled by rejections"
+    },
+    {
+      "code": 6013,
+      "name": "TimelockNotElapsed",
+      "msg": "Transaction cannot be executed until the wallet's min_delay has elapsed"
+    },
+    {
+      "code": 6014,
+      "name": "Expired",
+      "msg": "Transaction has expired"
+    },
+    {
+      "code": 6015,
+      "name": "ZeroWeight",
+      "msg": "Members must have a weight greater than zero"
     }
   ]
 };
@@ -522,7 +1092,9 @@ export const IDL: MultisigDemo = {
         {
           "name": "members",
           "type": {
-            "vec": "publicKey"
+            "vec": {
+              "defined": "Member"
+            }
           }
         }
       ]
@@ -530,7 +1102,10 @@ export const IDL: MultisigDemo = {
     {
       "name": "newTransaction",
       "docs": [
-        "Initialize a new [MultisigTransaction]."
+        "Initialize a new [MultisigTransaction]. Upon execution, the [state::Vault]",
+        "at `vault_index` signs alongside the [MultisigWallet].",
+        "The transaction expires `ttl` seconds after creation, or never if `ttl`",
+        "is zero. [None] falls back on the wallet's `default_ttl`."
       ],
       "accounts": [
         {
@@ -569,6 +1144,16 @@ export const IDL: MultisigDemo = {
               "defined": "Instruction"
             }
           }
+        },
+        {
+          "name": "vaultIndex",
+          "type": "u8"
+        },
+        {
+          "name": "ttl",
+          "type": {
+            "option": "u32"
+          }
         }
       ]
     },
@@ -620,6 +1205,31 @@ export const IDL: MultisigDemo = {
       ],
       "args": []
     },
+    {
+      "name": "reject",
+      "docs": [
+        "Reject a [MultisigTransaction]. Once enough members reject it that the",
+        "threshold can no longer be reached, the transaction is cancelled."
+      ],
+      "accounts": [
+        {
+          "name": "member",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "multisigWallet",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "transaction",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
     {
       "name": "execute",
       "docs": [
@@ -652,6 +1262,22 @@ export const IDL: MultisigDemo = {
           "name": "multisigWallet",
           "isMut": true,
           "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
@@ -661,6 +1287,70 @@ export const IDL: MultisigDemo = {
         }
       ]
     },
+    {
+      "name": "changeMinDelay",
+      "accounts": [
+        {
+          "name": "multisigWallet",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "minDelay",
+          "type": "u32"
+        }
+      ]
+    },
+    {
+      "name": "changeDefaultTtl",
+      "accounts": [
+        {
+          "name": "multisigWallet",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "defaultTtl",
+          "type": "u32"
+        }
+      ]
+    },
     {
       "name": "changeMembers",
       "accounts": [
@@ -668,16 +1358,59 @@ export const IDL: MultisigDemo = {
           "name": "multisigWallet",
           "isMut": true,
           "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Funds additional rent when the multisig account grows,",
+            "and receives the excess rent when it shrinks, unless that would",
+            "leave it below its own rent-exempt minimum.",
+            "Typically one of the wallet's vaults, which also signs upon execution."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
           "name": "members",
           "type": {
-            "vec": "publicKey"
+            "vec": {
+              "defined": "Member"
+            }
           }
         }
       ]
+    },
+    {
+      "name": "closeTransaction",
+      "docs": [
+        "Close a [MultisigTransaction] that was executed, cancelled, expired, or that",
+        "belongs to a previous wallet configuration, and return its rent to the proposer."
+      ],
+      "accounts": [
+        {
+          "name": "multisigWallet",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "transaction",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "proposer",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": []
     }
   ],
   "accounts": [
@@ -700,20 +1433,38 @@ export const IDL: MultisigDemo = {
             "name": "members",
             "docs": [
               "Members able to create and approve a [MultisigTransaction] owned by this",
-              "multisig."
+              "multisig, sorted by their key."
             ],
             "type": {
-              "vec": "publicKey"
+              "vec": {
+                "defined": "Member"
+              }
             }
           },
           {
             "name": "threshold",
             "docs": [
-              "At least this many members needed to approve [MultisigTransaction]",
-              "before it can be executed."
+              "At least this much total [Member.weight] needs to approve a",
+              "[MultisigTransaction] before it can be executed."
             ],
             "type": "u16"
           },
+          {
+            "name": "minDelay",
+            "docs": [
+              "Minimum number of seconds between a [MultisigTransaction] first reaching",
+              "the threshold and its execution. Only changeable through [Administration]."
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "defaultTtl",
+            "docs": [
+              "Seconds until a new [MultisigTransaction] expires, unless the proposer",
+              "specifies otherwise. Zero means that transactions don't expire by default."
+            ],
+            "type": "u32"
+          },
           {
             "name": "txNonce",
             "docs": [
@@ -722,11 +1473,13 @@ export const IDL: MultisigDemo = {
             "type": "u64"
           },
           {
-            "name": "memberSetSeqno",
+            "name": "configSeqno",
             "docs": [
-              "This is used to make sure that a transaction created with owner set X",
-              "cannot be approved by a new owner set Y. This handles the edge cases",
-              "where a user approves a transaction and then leaves multisig membership."
+              "Incremented by every [Administration] change, so that a transaction",
+              "created under configuration X cannot be approved or executed under a",
+              "new configuration Y. This handles the edge cases where a user approves",
+              "a transaction and then leaves multisig membership, or where the",
+              "threshold or timelock change while a transaction is pending."
             ],
             "type": "u32"
           },
@@ -756,6 +1509,13 @@ export const IDL: MultisigDemo = {
               }
             }
           },
+          {
+            "name": "vaultIndex",
+            "docs": [
+              "The [Vault] that signs alongside the [MultisigWallet] during execution."
+            ],
+            "type": "u8"
+          },
           {
             "name": "multisigWallet",
             "docs": [
@@ -777,9 +1537,22 @@ export const IDL: MultisigDemo = {
             }
           },
           {
-            "name": "memberSetSeqno",
+            "name": "rejected",
+            "docs": [
+              "Keeps track of which accounts rejected the transaction, and when.",
+              "`rejected[i]` is Some iff `[MultisigWallet.members[i]]` rejected the transaction.",
+              "A member cannot have both approved and rejected the same transaction."
+            ],
+            "type": {
+              "vec": {
+                "option": "i64"
+              }
+            }
+          },
+          {
+            "name": "configSeqno",
             "docs": [
-              "Saved to ensure that no approvals occur on a different set of memberships."
+              "Saved to ensure that no approvals occur under a different wallet configuration."
             ],
             "type": "u32"
           },
@@ -790,6 +1563,16 @@ export const IDL: MultisigDemo = {
             ],
             "type": "i64"
           },
+          {
+            "name": "expiresAt",
+            "docs": [
+              "Unix timestamp after which the transaction can no longer be approved or executed,",
+              "or [None] if it doesn't expire."
+            ],
+            "type": {
+              "option": "i64"
+            }
+          },
           {
             "name": "proposer",
             "docs": [
@@ -806,6 +1589,16 @@ export const IDL: MultisigDemo = {
               "option": "publicKey"
             }
           },
+          {
+            "name": "thresholdReachedAt",
+            "docs": [
+              "When the transaction reached the approval threshold, which starts the",
+              "[MultisigWallet.min_delay] timelock. Cleared when approvals fall below it."
+            ],
+            "type": {
+              "option": "i64"
+            }
+          },
           {
             "name": "executedAt",
             "docs": [
@@ -814,12 +1607,42 @@ export const IDL: MultisigDemo = {
             "type": {
               "option": "i64"
             }
+          },
+          {
+            "name": "cancelledAt",
+            "docs": [
+              "If/when enough members rejected the transaction that the threshold",
+              "can no longer be reached."
+            ],
+            "type": {
+              "option": "i64"
+            }
           }
         ]
       }
     }
   ],
   "types": [
+    {
+      "name": "Member",
+      "docs": [
+        "A member of a [MultisigWallet], whose approval counts `weight` towards",
+        "the wallet's threshold."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "key",
+            "type": "publicKey"
+          },
+          {
+            "name": "weight",
+            "type": "u16"
+          }
+        ]
+      }
+    },
     {
       "name": "Instruction",
       "docs": [
@@ -892,11 +1715,298 @@ export const IDL: MultisigDemo = {
       }
     }
   ],
+  "events": [
+    {
+      "name": "MultisigCreated",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "base",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "members",
+          "type": {
+            "vec": {
+              "defined": "Member"
+            }
+          },
+          "index": false
+        },
+        {
+          "name": "threshold",
+          "type": "u16",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionProposed",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "vaultIndex",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "expiresAt",
+          "type": {
+            "option": "i64"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionApproved",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "member",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "approvedWeight",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionUnapproved",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "member",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "approvedWeight",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionRejected",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "member",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "cancelled",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionExecuted",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "executor",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionClosed",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transaction",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "proposer",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "ThresholdChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "threshold",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "MinDelayChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "minDelay",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "DefaultTtlChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "defaultTtl",
+          "type": "u32",
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "MembersChanged",
+      "fields": [
+        {
+          "name": "multisigWallet",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "members",
+          "type": {
+            "vec": {
+              "defined": "Member"
+            }
+          },
+          "index": false
+        },
+        {
+          "name": "configSeqno",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    }
+  ],
   "errors": [
     {
       "code": 6000,
       "name": "InvalidThreshold",
-      "msg": "Threshold must be <= the number of multisig wallet members"
+      "msg": "Threshold must be between 1 and the total weight of the multisig wallet members"
     },
     {
       "code": 6001,
@@ -906,7 +2016,7 @@ export const IDL: MultisigDemo = {
     {
       "code": 6002,
       "name": "TooFewMembers",
-      "msg": "Not enough members with the given threshold"
+      "msg": "Not enough member weight to reach the given threshold"
     },
     {
       "code": 6003,
@@ -915,8 +2025,8 @@ export const IDL: MultisigDemo = {
     },
     {
       "code": 6004,
-      "name": "InvalidMemberSetSeqno",
-      "msg": "The multisig wallet does not match the transaction's member_set_seqno"
+      "name": "InvalidConfigSeqno",
+      "msg": "The multisig wallet's configuration changed since the transaction was proposed"
     },
     {
       "code": 6005,
@@ -942,6 +2052,36 @@ export const IDL: MultisigDemo = {
       "code": 6009,
       "name": "AlreadyExecuted",
       "msg": "Transaction already executed"
+    },
+    {
+      "code": 6010,
+      "name": "NotCloseable",
+      "msg": "Only executed, cancelled or expired transactions, or those from a previous wallet configuration, can be closed"
+    },
+    {
+      "code": 6011,
+      "name": "AlreadyRejected",
+      "msg": "Signer already rejected this transaction"
+    },
+    {
+      "code": 6012,
+      "name": "AlreadyCancelled",
+      "msg": "Transaction was cancelled by rejections"
+    },
+    {
+      "code": 6013,
+      "name": "TimelockNotElapsed",
+      "msg": "Transaction cannot be executed until the wallet's min_delay has elapsed"
+    },
+    {
+      "code": 6014,
+      "name": "Expired",
+      "msg": "Transaction has expired"
+    },
+    {
+      "code": 6015,
+      "name": "ZeroWeight",
+      "msg": "Members must have a weight greater than zero"
     }
   ]
 };
@@ -2,15 +2,26 @@ import * as anchor from "@project-serum/anchor";
 import {Instruction, Program} from "@project-serum/anchor";
 import { MultisigDemo } from "./multisig_demo";
 import {AccountMeta, Commitment, Transaction, TransactionInstruction, TransactionSignature} from "@solana/web3.js";
-import {findMultisigTransactionAddress, findMultisigWalletAddress} from "./address";
+import {findMultisigTransactionAddress, findMultisigWalletAddress, findVaultAddress} from "./address";
+
+/// A member whose approval counts `weight` towards the wallet's threshold.
+export type Member = {
+  key: anchor.web3.PublicKey;
+  weight: number;
+};
+
+/// Members given by their key alone get a weight of one.
+export type MemberSpec = anchor.web3.PublicKey | Member;
 
 /// Replicated the type here because pulling it out of the IDL is beyond my TS skills.
 export type MultisigWallet = {
   base: anchor.web3.PublicKey;
-  members: anchor.web3.PublicKey[];
+  members: Member[];
   threshold: number;
+  minDelay: number;
+  defaultTtl: number;
   txNonce: anchor.BN;
-  memberSetSeqno: number;
+  configSeqno: number;
   bump: number;
 };
 
@@ -71,6 +82,16 @@ export class MultisigMember {
     );
   }
 
+  /// Vaults hold funds on behalf of the multisig wallet,
+  /// and sign alongside it when a transaction is executed.
+  vaultAddress(index: number) {
+    return findVaultAddress(
+      this.walletAddress,
+      index,
+      this.program.programId,
+    );
+  }
+
   /// A common execution routine used all the RPC methods.
   async sendTx(
     instructions: TransactionInstruction[],
@@ -89,16 +110,23 @@ export class MultisigMember {
   }
 
   /// Optional commitment is passed to a refresh of the multisig wallet metadata.
+  /// The vault at `vaultIndex` signs alongside the multisig wallet upon execution.
+  /// The transaction expires `ttl` seconds after creation, or never if `ttl` is 0.
+  /// If null, the wallet's default TTL applies.
   async newTransactionIx(
     instructions: TransactionInstruction[],
     commitment?: Commitment,
+    vaultIndex: number = 0,
+    ttl: number | null = null,
   ): Promise<TransactionInstruction> {
 
     // Refresh our data, so that we're sure we're using an up-to-date nonce.
     await this.refreshWallet(commitment);
     const multisigTransaction = this.nextTransactionAddress();
     return await this.program.methods.newTransaction(
-      instructions
+      instructions,
+      vaultIndex,
+      ttl,
     )
       .accounts({
         proposer: this.signer.publicKey,
@@ -136,6 +164,19 @@ export class MultisigMember {
       .instruction();
   }
 
+  async rejectIx(
+    transaction: anchor.web3.PublicKey,
+  ): Promise<TransactionInstruction> {
+    return await this.program.methods.reject()
+      .accounts({
+        member: this.signer.publicKey,
+        multisigWallet: this.walletAddress,
+        transaction,
+      })
+      .signers([this.signer])
+      .instruction();
+  }
+
   async executeIx(
     transaction: anchor.web3.PublicKey,
     commitment?: Commitment,
@@ -143,6 +184,8 @@ export class MultisigMember {
     const txData = await this.program.account.multisigTransaction.fetch(
       transaction, commitment,
     );
+    // The vault is a PDA, its signature is provided by the program during execution.
+    const vault = this.vaultAddress(txData.vaultIndex);
     let acts: AccountMeta[] = [];
     (txData.instructions as TransactionInstruction[]).map(
       (ix) => {
@@ -152,7 +195,9 @@ export class MultisigMember {
           isWritable: false,
         });
         ix.keys.map((key) => {
-          if (!key.pubkey.equals(this.walletAddress)) {
+          if (key.pubkey.equals(vault)) {
+            acts = acts.concat([{...key, isSigner: false}]);
+          } else if (!key.pubkey.equals(this.walletAddress)) {
             acts = acts.concat([key]);
           }
         });
@@ -174,12 +219,31 @@ export class MultisigMember {
       .instruction();
   }
 
+  /// Closes an executed or stale transaction, returning its rent to the proposer.
+  async closeTransactionIx(
+    transaction: anchor.web3.PublicKey,
+    commitment?: Commitment,
+  ): Promise<TransactionInstruction> {
+    const txData = await this.program.account.multisigTransaction.fetch(
+      transaction, commitment,
+    );
+    return await this.program.methods.closeTransaction()
+      .accounts({
+        multisigWallet: this.walletAddress,
+        transaction,
+        proposer: txData.proposer,
+      })
+      .instruction();
+  }
+
   async newTransactionRpc(
     instructions: TransactionInstruction[],
     confirmOptions?: anchor.web3.ConfirmOptions,
+    vaultIndex: number = 0,
+    ttl: number | null = null,
   ): Promise<TransactionSignature> {
 
-    const ix = await this.newTransactionIx(instructions, confirmOptions?.commitment);
+    const ix = await this.newTransactionIx(instructions, confirmOptions?.commitment, vaultIndex, ttl);
     return await this.sendTx(
       [ix],
       [this.signer],
@@ -213,6 +277,19 @@ export class MultisigMember {
     );
   }
 
+  async rejectRpc(
+    transaction: anchor.web3.PublicKey,
+    confirmOptions?: anchor.web3.ConfirmOptions,
+  ): Promise<TransactionSignature> {
+
+    const ix = await this.rejectIx(transaction);
+    return await this.sendTx(
+      [ix],
+      [this.signer],
+      confirmOptions,
+    );
+  }
+
   async executeRpc(
     transaction: anchor.web3.PublicKey,
     confirmOptions?: anchor.web3.ConfirmOptions,
@@ -226,15 +303,32 @@ export class MultisigMember {
     );
   }
 
+  async closeTransactionRpc(
+    transaction: anchor.web3.PublicKey,
+    confirmOptions?: anchor.web3.ConfirmOptions,
+  ): Promise<TransactionSignature> {
+
+    const ix = await this.closeTransactionIx(transaction, confirmOptions?.commitment);
+    return await this.sendTx(
+      [ix],
+      [this.signer],
+      confirmOptions,
+    );
+  }
+
   async newTransactionAndApproveRpc(
     instructions: TransactionInstruction[],
     confirmOptions?: anchor.web3.ConfirmOptions,
+    vaultIndex: number = 0,
+    ttl: number | null = null,
   ): Promise<TransactionSignature> {
     // Refresh our data, so that we're sure we're using an up-to-date nonce.
     await this.refreshWallet(confirmOptions?.commitment);
     const multisigTransaction = this.nextTransactionAddress();
     const newTransactionIx = await this.program.methods.newTransaction(
-      instructions
+      instructions,
+      vaultIndex,
+      ttl,
     )
       .accounts({
         proposer: this.signer.publicKey,
@@ -266,11 +360,16 @@ export class MultisigMember {
     let ix = await this.program.methods.changeThreshold(threshold)
       .accounts({
         multisigWallet: this.walletAddress,
+        // Pays for (or is refunded) any change in the wallet's rent.
+        payer: this.vaultAddress(0),
+        systemProgram: anchor.web3.SystemProgram.programId,
       })
       .instruction();
     const multisigTransaction = this.nextTransactionAddress();
     const newTransactionIx = await this.program.methods.newTransaction(
-      [ix]
+      [ix],
+      0,
+      null,
     )
       .accounts({
         proposer: this.signer.publicKey,
@@ -285,18 +384,83 @@ export class MultisigMember {
       [newTransactionIx], [this.signer], confirmOptions);
   }
 
+  async proposeChangeMinDelay(
+    minDelay: number,
+    confirmOptions?: anchor.web3.ConfirmOptions,
+  ): Promise<TransactionSignature> {
+    let ix = await this.program.methods.changeMinDelay(minDelay)
+      .accounts({
+        multisigWallet: this.walletAddress,
+        // Pays for (or is refunded) any change in the wallet's rent.
+        payer: this.vaultAddress(0),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+    const multisigTransaction = this.nextTransactionAddress();
+    const newTransactionIx = await this.program.methods.newTransaction(
+      [ix],
+      0,
+      null,
+    )
+      .accounts({
+        proposer: this.signer.publicKey,
+        multisigWallet: this.walletAddress,
+        transaction: multisigTransaction,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([this.signer])
+      .instruction();
+    return await this.sendTx(
+      [newTransactionIx], [this.signer], confirmOptions);
+  }
+
+  async proposeChangeDefaultTtl(
+    defaultTtl: number,
+    confirmOptions?: anchor.web3.ConfirmOptions,
+  ): Promise<TransactionSignature> {
+    let ix = await this.program.methods.changeDefaultTtl(defaultTtl)
+      .accounts({
+        multisigWallet: this.walletAddress,
+        // Pays for (or is refunded) any change in the wallet's rent.
+        payer: this.vaultAddress(0),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+    const multisigTransaction = this.nextTransactionAddress();
+    const newTransactionIx = await this.program.methods.newTransaction(
+      [ix],
+      0,
+      null,
+    )
+      .accounts({
+        proposer: this.signer.publicKey,
+        multisigWallet: this.walletAddress,
+        transaction: multisigTransaction,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([this.signer])
+      .instruction();
+    return await this.sendTx(
+      [newTransactionIx], [this.signer], confirmOptions);
+  }
+
   async proposeChangeMembers(
-    members: anchor.web3.PublicKey[],
+    members: MemberSpec[],
     confirmOptions?: anchor.web3.ConfirmOptions,
   ): Promise<TransactionSignature> {
-    let ix = await this.program.methods.changeMembers(members)
+    let ix = await this.program.methods.changeMembers(sortMembers(members))
       .accounts({
         multisigWallet: this.walletAddress,
+        // Pays for (or is refunded) any change in the wallet's rent.
+        payer: this.vaultAddress(0),
+        systemProgram: anchor.web3.SystemProgram.programId,
       })
       .instruction();
     const multisigTransaction = this.nextTransactionAddress();
     const newTransactionIx = await this.program.methods.newTransaction(
-      [ix]
+      [ix],
+      0,
+      null,
     )
       .accounts({
         proposer: this.signer.publicKey,
@@ -318,7 +482,7 @@ export async function newMultisigRpc(
   base: anchor.web3.Signer,
   payer: anchor.web3.Signer,
   threshold: number,
-  members: anchor.web3.PublicKey[],
+  members: MemberSpec[],
   program: Program<MultisigDemo>,
   confirmOptions?: anchor.web3.ConfirmOptions,
 ): Promise<TransactionSignature> {
@@ -328,7 +492,7 @@ export async function newMultisigRpc(
   );
   return await program.methods.newMultisig(
     threshold,
-    members,
+    sortMembers(members),
   )
     .accounts({
       base: base.publicKey,
@@ -342,3 +506,17 @@ export async function newMultisigRpc(
     ])
     .rpc(confirmOptions);
 }
+
+export function toMember(member: MemberSpec): Member {
+  return member instanceof anchor.web3.PublicKey ? {key: member, weight: 1} : member;
+}
+
+/// The program stores members in sorted (byte-wise) order of their keys,
+/// so that a member's index in the approvals can be found by binary search.
+export function sortMembers(
+  members: MemberSpec[],
+): Member[] {
+  return members
+    .map(toMember)
+    .sort((a, b) => Buffer.compare(a.key.toBuffer(), b.key.toBuffer()));
+}
@@ -3,17 +3,18 @@ use anchor_lang::error_code;
 
 
 #[error_code]
+#[derive(PartialEq, Eq)]
 pub enum MultisigError {
-    #[msg("Threshold must be <= the number of multisig wallet members")]
+    #[msg("Threshold must be between 1 and the total weight of the multisig wallet members")]
     InvalidThreshold,
     #[msg("Members of a multisig must be unique addresses")]
     DuplicateMembers,
-    #[msg("Not enough members with the given threshold")]
+    #[msg("Not enough member weight to reach the given threshold")]
     TooFewMembers,
     #[msg("Not a current member of the multisig wallet")]
     NotAMember,
-    #[msg("The multisig wallet does not match the transaction's member_set_seqno")]
-    InvalidMemberSetSeqno,
+    #[msg("The multisig wallet's configuration changed since the transaction was proposed")]
+    InvalidConfigSeqno,
     #[msg("The transaction does not belong to the provided multisig")]
     InvalidMultisigReference,
     #[msg("Signer already approved this transaction")]
@@ -24,4 +25,16 @@ pub enum MultisigError {
     NotEnoughApprovals,
     #[msg("Transaction already executed")]
     AlreadyExecuted,
+    #[msg("Only executed, cancelled or expired transactions, or those from a previous wallet configuration, can be closed")]
+    NotCloseable,
+    #[msg("Signer already rejected this transaction")]
+    AlreadyRejected,
+    #[msg("Transaction was cancelled by rejections")]
+    AlreadyCancelled,
+    #[msg("Transaction cannot be executed until the wallet's min_delay has elapsed")]
+    TimelockNotElapsed,
+    #[msg("Transaction has expired")]
+    Expired,
+    #[msg("Members must have a weight greater than zero")]
+    ZeroWeight,
 }
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use crate::state::Member;
+
+/// Emitted when a new [crate::state::MultisigWallet] is initialized.
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultisigCreated {
+    pub multisig_wallet: Pubkey,
+    pub base: Pubkey,
+    /// The members, in their canonical (sorted) order.
+    pub members: Vec<Member>,
+    pub threshold: u16,
+}
+
+/// Emitted when a member proposes a new [crate::state::MultisigTransaction].
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionProposed {
+    pub multisig_wallet: Pubkey,
+    pub transaction: Pubkey,
+    pub proposer: Pubkey,
+    pub vault_index: u8,
+    pub config_seqno: u32,
+    pub expires_at: Option<i64>,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionApproved {
+    pub multisig_wallet: Pubkey,
+    pub transaction: Pubkey,
+    pub member: Pubkey,
+    /// The combined weight of the approvals, including this one.
+    pub approved_weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionUnapproved {
+    pub multisig_wallet: Pubkey,
+    pub transaction: Pubkey,
+    pub member: Pubkey,
+    /// The combined weight of the remaining approvals.
+    pub approved_weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionRejected {
+    pub multisig_wallet: Pubkey,
+    pub transaction: Pubkey,
+    pub member: Pubkey,
+    /// Whether this rejection cancelled the transaction.
+    pub cancelled: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted after all of the transaction's instructions were invoked,
+/// so after any events that they emitted themselves.
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionExecuted {
+    pub multisig_wallet: Pubkey,
+    pub transaction: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionClosed {
+    pub multisig_wallet: Pubkey,
+    pub transaction: Pubkey,
+    /// Receives the transaction account's rent.
+    pub proposer: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThresholdChanged {
+    pub multisig_wallet: Pubkey,
+    pub threshold: u16,
+    /// The wallet's new configuration seqno.
+    pub config_seqno: u32,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinDelayChanged {
+    pub multisig_wallet: Pubkey,
+    pub min_delay: u32,
+    /// The wallet's new configuration seqno.
+    pub config_seqno: u32,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DefaultTtlChanged {
+    pub multisig_wallet: Pubkey,
+    pub default_ttl: u32,
+    /// The wallet's new configuration seqno.
+    pub config_seqno: u32,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembersChanged {
+    pub multisig_wallet: Pubkey,
+    /// The new members, in their canonical (sorted) order.
+    pub members: Vec<Member>,
+    /// The wallet's new configuration seqno.
+    pub config_seqno: u32,
+}
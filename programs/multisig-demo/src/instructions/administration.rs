@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use vipers::{invariant, unwrap_int};
 use crate::error::MultisigError;
-use crate::state::MultisigWallet;
+use crate::events::{DefaultTtlChanged, MembersChanged, MinDelayChanged, ThresholdChanged};
+use crate::state::{Member, MultisigWallet};
 
 /// Used for actions that modify the multisig account itself,
 /// and which therefore require the multisig account to sign
@@ -12,24 +14,109 @@ use crate::state::MultisigWallet;
 pub struct Administration<'info> {
     #[account(mut, signer)]
     multisig_wallet: Account<'info, MultisigWallet>,
+    /// Funds additional rent when the multisig account grows,
+    /// and receives the excess rent when it shrinks, unless that would
+    /// leave it below its own rent-exempt minimum.
+    /// Typically one of the wallet's vaults, which also signs upon execution.
+    #[account(mut)]
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
 }
 
 impl<'info> Administration<'info> {
     pub fn handle_change_threshold(&mut self, threshold: u16) -> Result<()> {
-        invariant!(threshold as usize <= self.multisig_wallet.members.len(),
+        // Cannot have a threshold of zero
+        invariant!(threshold > 0, MultisigError::InvalidThreshold);
+        invariant!(threshold as u64 <= MultisigWallet::total_weight(&self.multisig_wallet.members),
             MultisigError::InvalidThreshold
         );
         self.multisig_wallet.threshold = threshold;
+        // Invalidate all pending transactions, so that none of them can be
+        // approved or executed under rules other than those it was proposed under.
+        self.multisig_wallet.config_seqno = unwrap_int!(
+            self.multisig_wallet.config_seqno.checked_add(1));
+        emit!(ThresholdChanged {
+            multisig_wallet: self.multisig_wallet.key(),
+            threshold,
+            config_seqno: self.multisig_wallet.config_seqno,
+        });
         Ok(())
     }
 
-    pub fn handle_change_members(self: &mut Self, members: Vec<Pubkey>) -> Result<()> {
-        invariant!(members.len() >= self.multisig_wallet.threshold as usize,
+    pub fn handle_change_min_delay(&mut self, min_delay: u32) -> Result<()> {
+        self.multisig_wallet.min_delay = min_delay;
+        self.multisig_wallet.config_seqno = unwrap_int!(
+            self.multisig_wallet.config_seqno.checked_add(1));
+        emit!(MinDelayChanged {
+            multisig_wallet: self.multisig_wallet.key(),
+            min_delay,
+            config_seqno: self.multisig_wallet.config_seqno,
+        });
+        Ok(())
+    }
+
+    pub fn handle_change_default_ttl(&mut self, default_ttl: u32) -> Result<()> {
+        self.multisig_wallet.default_ttl = default_ttl;
+        self.multisig_wallet.config_seqno = unwrap_int!(
+            self.multisig_wallet.config_seqno.checked_add(1));
+        emit!(DefaultTtlChanged {
+            multisig_wallet: self.multisig_wallet.key(),
+            default_ttl,
+            config_seqno: self.multisig_wallet.config_seqno,
+        });
+        Ok(())
+    }
+
+    pub fn handle_change_members(self: &mut Self, members: Vec<Member>) -> Result<()> {
+        invariant!(MultisigWallet::total_weight(&members) >= self.multisig_wallet.threshold as u64,
             MultisigError::TooFewMembers,
         );
-        self.multisig_wallet.members = members;
-        self.multisig_wallet.member_set_seqno = unwrap_int!(
-            self.multisig_wallet.member_set_seqno.checked_add(1));
+        self.resize_wallet(members.len())?;
+        self.multisig_wallet.members = MultisigWallet::canonical_members(&members)?;
+        self.multisig_wallet.config_seqno = unwrap_int!(
+            self.multisig_wallet.config_seqno.checked_add(1));
+        emit!(MembersChanged {
+            multisig_wallet: self.multisig_wallet.key(),
+            members: self.multisig_wallet.members.clone(),
+            config_seqno: self.multisig_wallet.config_seqno,
+        });
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Reallocate the multisig account to fit `num_members`, keeping it rent-exempt.
+    /// The payer covers any additional rent, and is refunded any excess rent.
+    /// An empty payer, such as an unfunded vault, can't take a refund smaller than
+    /// its own rent-exempt minimum, so the excess then stays with the wallet.
+    fn resize_wallet(&mut self, num_members: usize) -> Result<()> {
+        let wallet = self.multisig_wallet.to_account_info();
+        let new_space = MultisigWallet::space(num_members);
+        if new_space == wallet.data_len() {
+            return Ok(());
+        }
+        let rent = Rent::get()?;
+        let rent_exempt_balance = rent.minimum_balance(new_space);
+        let current_balance = wallet.lamports();
+        if rent_exempt_balance > current_balance {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.payer.to_account_info(),
+                        to: wallet.clone(),
+                    },
+                ),
+                unwrap_int!(rent_exempt_balance.checked_sub(current_balance)),
+            )?;
+        } else if current_balance > rent_exempt_balance {
+            let refund = unwrap_int!(current_balance.checked_sub(rent_exempt_balance));
+            let payer = self.payer.to_account_info();
+            let refunded_balance = unwrap_int!(payer.lamports().checked_add(refund));
+            if rent.is_exempt(refunded_balance, payer.data_len()) {
+                **wallet.try_borrow_mut_lamports()? = rent_exempt_balance;
+                **payer.try_borrow_mut_lamports()? = refunded_balance;
+            }
+        }
+        wallet.realloc(new_space, false)?;
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
-use vipers::{assert_keys_eq, invariant};
-use crate::state::{MultisigTransaction, MultisigWallet};
+use vipers::{assert_keys_eq, invariant, unwrap_opt};
+use crate::state::{MultisigTransaction, MultisigWallet, Vault};
 use crate::error::MultisigError;
-use crate::gen_multisig_wallet_seeds;
+use crate::events::{TransactionApproved, TransactionExecuted, TransactionRejected, TransactionUnapproved};
+use crate::{gen_multisig_wallet_seeds, gen_vault_seeds};
 
 #[derive(Accounts)]
 pub struct Approval<'info> {
@@ -22,35 +23,62 @@ impl<'info> Approval<'info> {
         assert_keys_eq!(tx.multisig_wallet, msig.key(),
             MultisigError::InvalidMultisigReference
         );
-        invariant!(msig.members.contains(&self.member.key()),
+        invariant!(msig.is_member(&self.member.key()),
             MultisigError::NotAMember,
         );
+        // Approvals are indexed by member, and counted against the threshold,
+        // so they're only meaningful for the configuration the transaction
+        // was created under.
+        invariant!(tx.config_seqno == msig.config_seqno,
+            MultisigError::InvalidConfigSeqno,
+        );
         // The transaction should not be executed yet.
         invariant!(tx.executed_at.is_none(), MultisigError::AlreadyExecuted);
         invariant!(tx.executor.is_none(), MultisigError::AlreadyExecuted);
+        // Nor should it have been cancelled.
+        invariant!(tx.cancelled_at.is_none(), MultisigError::AlreadyCancelled);
         Ok(())
     }
 
     pub fn handle_approve(&mut self) -> Result<()> {
         // First find the index where we need to mark a [true] in the transaction approvals.
         let member = self.member.key();
-        let member_idx = self.multisig_wallet.members.binary_search(&member)
-            .map_err(|_| MultisigError::NotAMember)?;
+        let member_idx = unwrap_opt!(self.multisig_wallet.member_index(&member),
+            MultisigError::NotAMember);
 
         // If the member has already approved, throw an error.
         // This allows preflight simulations to catch unnecessary approval transactions.
         let tx = &mut self.transaction;
         invariant!(tx.approved[member_idx].is_none(),
             MultisigError::AlreadyApproved);
-        tx.approved[member_idx] = Some(Clock::get()?.unix_timestamp);
+        let now = Clock::get()?.unix_timestamp;
+        invariant!(!tx.is_expired(now), MultisigError::Expired);
+        tx.approved[member_idx] = Some(now);
+        // Approving withdraws any previous rejection.
+        tx.rejected[member_idx] = None;
+
+        // The timelock starts when the threshold is reached. It restarts if approvals
+        // fall below the threshold before reaching it again, see [Self::handle_unapprove].
+        let approved_weight = tx.approved_weight(&self.multisig_wallet);
+        if tx.threshold_reached_at.is_none()
+            && approved_weight >= self.multisig_wallet.threshold as u64 {
+            tx.threshold_reached_at = Some(now);
+        }
+        emit!(TransactionApproved {
+            multisig_wallet: tx.multisig_wallet,
+            transaction: tx.key(),
+            member,
+            approved_weight,
+            timestamp: now,
+        });
         Ok(())
     }
 
     pub fn handle_unapprove(&mut self) -> Result<()> {
         // First find the index where we need to mark a [false] in the transaction approvals.
         let member = self.member.key();
-        let member_idx = self.multisig_wallet.members.binary_search(&member)
-            .map_err(|_| MultisigError::NotAMember)?;
+        let member_idx = unwrap_opt!(self.multisig_wallet.member_index(&member),
+            MultisigError::NotAMember);
 
         // If the member is already marked as unapproved, throw an error.
         // This allows preflight simulations to catch unnecessary unapproval transactions.
@@ -58,25 +86,87 @@ impl<'info> Approval<'info> {
         invariant!(tx.approved[member_idx].is_some(),
             MultisigError::AlreadyUnapproved);
         tx.approved[member_idx] = None;
+        // Falling below the threshold resets the timelock, so that the time
+        // spent above it earlier can't count towards a later execution.
+        let approved_weight = tx.approved_weight(&self.multisig_wallet);
+        if approved_weight < self.multisig_wallet.threshold as u64 {
+            tx.threshold_reached_at = None;
+        }
+        emit!(TransactionUnapproved {
+            multisig_wallet: tx.multisig_wallet,
+            transaction: tx.key(),
+            member,
+            approved_weight,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         Ok(())
     }
+
+    pub fn handle_reject(&mut self) -> Result<()> {
+        // First find the index where we need to mark the rejection.
+        let member = self.member.key();
+        let member_idx = unwrap_opt!(self.multisig_wallet.member_index(&member),
+            MultisigError::NotAMember);
+
+        // If the member has already rejected, throw an error.
+        // This allows preflight simulations to catch unnecessary rejection transactions.
+        let tx = &mut self.transaction;
+        invariant!(tx.rejected[member_idx].is_none(),
+            MultisigError::AlreadyRejected);
+        let now = Clock::get()?.unix_timestamp;
+        // Rejecting withdraws any previous approval.
+        tx.approved[member_idx] = None;
+        tx.rejected[member_idx] = Some(now);
+        // Withdrawing an approval resets the timelock, as in [Self::handle_unapprove].
+        if tx.approved_weight(&self.multisig_wallet) < self.multisig_wallet.threshold as u64 {
+            tx.threshold_reached_at = None;
+        }
+
+        // Cancel once the members who haven't rejected can no longer reach the threshold.
+        let msig = &self.multisig_wallet;
+        let remaining_weight = MultisigWallet::total_weight(&msig.members)
+            .saturating_sub(tx.rejected_weight(msig));
+        if remaining_weight < msig.threshold as u64 {
+            tx.cancelled_at = Some(now);
+        }
+        emit!(TransactionRejected {
+            multisig_wallet: tx.multisig_wallet,
+            transaction: tx.key(),
+            member,
+            cancelled: tx.cancelled_at.is_some(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
     pub fn handle_execute(&mut self, remaining_accounts: &[AccountInfo]) -> Result<()> {
         let msig = &self.multisig_wallet;
         // Approval check
-        let num_approvals: usize = self.transaction.approved
-            .iter()
-            .filter(|&approved| approved.is_some())
-            .count();
-        invariant!(num_approvals >= msig.threshold as usize, MultisigError::NotEnoughApprovals);
+        let approved_weight = self.transaction.approved_weight(msig);
+        invariant!(approved_weight >= msig.threshold as u64, MultisigError::NotEnoughApprovals);
+        // Expiry check
+        let now = Clock::get()?.unix_timestamp;
+        invariant!(!self.transaction.is_expired(now), MultisigError::Expired);
+        // Timelock check
+        if msig.min_delay > 0 {
+            let executable_at = unwrap_opt!(self.transaction.executable_at(msig),
+                MultisigError::TimelockNotElapsed);
+            invariant!(now >= executable_at, MultisigError::TimelockNotElapsed);
+        }
 
         let seeds = gen_multisig_wallet_seeds!(self.multisig_wallet);
+        // The vault holds no data, so its bump is re-derived rather than stored.
+        let multisig_wallet = self.multisig_wallet.key();
+        let vault_index = self.transaction.vault_index;
+        let (_, vault_bump) = Vault::find_address(&multisig_wallet, vault_index);
+        let vault_seeds = gen_vault_seeds!(multisig_wallet, vault_index, vault_bump);
 
         // Perform transaction as CPI
         for ix in self.transaction.instructions.iter() {
             solana_program::program::invoke_signed(
                 &ix.clone().into(),
                 remaining_accounts,
-                &[&seeds[..]],
+                &[&seeds[..], &vault_seeds[..]],
             )?;
         }
         // We need to reload the wallet here to persist any possible mutations
@@ -85,7 +175,13 @@ impl<'info> Approval<'info> {
 
         let tx = &mut self.transaction;
         tx.executor = Some(self.member.key());
-        tx.executed_at = Some(Clock::get()?.unix_timestamp);
+        tx.executed_at = Some(now);
+        emit!(TransactionExecuted {
+            multisig_wallet,
+            transaction: tx.key(),
+            executor: self.member.key(),
+            timestamp: now,
+        });
         Ok(())
     }
 }
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use vipers::invariant;
+use crate::state::{MultisigTransaction, MultisigWallet};
+use crate::error::MultisigError;
+use crate::events::TransactionClosed;
+
+/// Closes a [MultisigTransaction] that can no longer be executed,
+/// returning its rent to the original proposer.
+/// Anyone may do this, since the lamports always go back to the proposer.
+#[derive(Accounts)]
+pub struct CloseTransaction<'info> {
+    multisig_wallet: Account<'info, MultisigWallet>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = multisig_wallet @ MultisigError::InvalidMultisigReference,
+        has_one = proposer,
+    )]
+    transaction: Account<'info, MultisigTransaction>,
+    /// CHECK: Only receives lamports, and must match [MultisigTransaction.proposer].
+    #[account(mut)]
+    proposer: UncheckedAccount<'info>,
+}
+
+impl<'info> CloseTransaction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        invariant!(self.transaction.is_closeable(&self.multisig_wallet, now),
+            MultisigError::NotCloseable,
+        );
+        Ok(())
+    }
+
+    pub fn handle(&self) {
+        emit!(TransactionClosed {
+            multisig_wallet: self.multisig_wallet.key(),
+            transaction: self.transaction.key(),
+            proposer: self.proposer.key(),
+        });
+    }
+}
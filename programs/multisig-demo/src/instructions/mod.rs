@@ -2,8 +2,10 @@ pub mod new_multisig;
 pub mod new_transaction;
 pub mod approval;
 pub mod administration;
+pub mod close_transaction;
 
 pub use new_multisig::*;
 pub use new_transaction::*;
 pub use approval::*;
 pub use administration::*;
+pub use close_transaction::*;
@@ -2,11 +2,12 @@ use anchor_lang::prelude::*;
 use solana_program::pubkey::Pubkey;
 use vipers::invariant;
 use anchor_lang::prelude::{Account, Program, Signer, System};
-use crate::state::MultisigWallet;
+use crate::state::{Member, MultisigWallet};
 use crate::error::MultisigError;
+use crate::events::MultisigCreated;
 
 #[derive(Accounts)]
-#[instruction(threshold: u16, members: Vec<Pubkey>)]
+#[instruction(threshold: u16, members: Vec<Member>)]
 pub struct NewMultisig<'info> {
     /// Entropy to ensure a unique address.
     /// Improves on a design flaw in Project Serum's multisig implementation.
@@ -31,26 +32,33 @@ pub struct NewMultisig<'info> {
 }
 
 impl<'info> NewMultisig<'info> {
-    pub fn validate(&self, threshold: &u16, members: &Vec<Pubkey>) -> Result<()> {
+    pub fn validate(&self, threshold: u16, members: &[Member]) -> Result<()> {
         // Cannot have a threshold of zero
-        invariant!(*threshold > 0, MultisigError::InvalidThreshold);
-        // Cannot have a threshold higher than the number of members
-        invariant!(*threshold as usize <= members.len(), MultisigError::InvalidThreshold);
-        // Members must be unique
-        let mut deduped = members.clone();
-        deduped.dedup();
-        invariant!(members.len() == deduped.len(), MultisigError::DuplicateMembers);
+        invariant!(threshold > 0, MultisigError::InvalidThreshold);
+        // Cannot have a threshold higher than the members can reach together
+        invariant!(threshold as u64 <= MultisigWallet::total_weight(members),
+            MultisigError::InvalidThreshold);
+        // Members must be unique, and have some weight
+        MultisigWallet::canonical_members(members)?;
         Ok(())
     }
 
-    pub fn handle(&mut self, threshold: u16, members: Vec<Pubkey>, bump: u8) -> Result<()> {
+    pub fn handle(&mut self, threshold: u16, members: Vec<Member>, bump: u8) -> Result<()> {
         let msig = &mut self.multisig_wallet;
-        msig.member_set_seqno = 0;
+        msig.config_seqno = 0;
         msig.tx_nonce = 0;
-        msig.members = members.clone();
+        msig.members = MultisigWallet::canonical_members(&members)?;
         msig.base = self.base.key();
         msig.threshold = threshold;
+        msig.min_delay = 0;
+        msig.default_ttl = 0;
         msig.bump = bump;
+        emit!(MultisigCreated {
+            multisig_wallet: msig.key(),
+            base: msig.base,
+            members: msig.members.clone(),
+            threshold,
+        });
         Ok(())
     }
 }
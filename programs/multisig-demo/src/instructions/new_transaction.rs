@@ -2,9 +2,10 @@ use anchor_lang::prelude::*;
 use vipers::invariant;
 use crate::state::{Instruction, MultisigTransaction, MultisigWallet};
 use crate::error::MultisigError;
+use crate::events::TransactionProposed;
 
 #[derive(Accounts)]
-#[instruction(instructions: Vec<Instruction>)]
+#[instruction(instructions: Vec<Instruction>, vault_index: u8, ttl: Option<u32>)]
 pub struct NewTransaction<'info> {
     /// Must be a member of the given [multisig_wallet].
     #[account(mut)]
@@ -30,31 +31,55 @@ pub struct NewTransaction<'info> {
 
 impl<'info> NewTransaction<'info> {
     pub fn validate(&self) -> Result<()> {
-        invariant!(self.multisig_wallet.members.contains(&self.proposer.key()),
+        invariant!(self.multisig_wallet.is_member(&self.proposer.key()),
             MultisigError::NotAMember,
         );
         Ok(())
     }
 
-    pub fn handle(&mut self, instructions: Vec<Instruction>) -> Result<()> {
+    pub fn handle(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+    ) -> Result<()> {
         let tx = &mut self.transaction;
         // Transaction Content
         tx.instructions = instructions;
+        tx.vault_index = vault_index;
         // Authorization Controls
         tx.multisig_wallet = self.multisig_wallet.key();
         tx.approved = (0..self.multisig_wallet.members.len())
             .map(|_| None)
             .collect();
-        tx.member_set_seqno = self.multisig_wallet.member_set_seqno;
+        tx.rejected = (0..self.multisig_wallet.members.len())
+            .map(|_| None)
+            .collect();
+        tx.config_seqno = self.multisig_wallet.config_seqno;
         // Transaction History
         tx.created_at = Clock::get()?.unix_timestamp;
+        // Fall back on the wallet's default time-to-live, where zero means no expiry.
+        tx.expires_at = match ttl.unwrap_or(self.multisig_wallet.default_ttl) {
+            0 => None,
+            ttl => Some(tx.created_at.saturating_add(ttl as i64)),
+        };
         tx.proposer = self.proposer.key();
+        tx.threshold_reached_at = None;
         tx.executed_at = None;
         tx.executor = None;
+        tx.cancelled_at = None;
 
         // Increment the multisig nonce, so that the next transaction has a unique public key.
         let msig = &mut self.multisig_wallet;
         msig.tx_nonce += 1;
+        emit!(TransactionProposed {
+            multisig_wallet: msig.key(),
+            transaction: tx.key(),
+            proposer: tx.proposer,
+            vault_index,
+            config_seqno: tx.config_seqno,
+            expires_at: tx.expires_at,
+        });
         Ok(())
     }
 }
@@ -1,9 +1,10 @@
 pub mod state;
-mod error;
+pub mod error;
+pub mod events;
 mod instructions;
 
 use anchor_lang::prelude::*;
-use state::Instruction;
+use state::{Instruction, Member};
 
 use instructions::*;
 
@@ -15,11 +16,11 @@ pub mod multisig_demo {
     use super::*;
 
     /// Initialize a new [MultisigWallet].
-    #[access_control(ctx.accounts.validate(&threshold, &members))]
+    #[access_control(ctx.accounts.validate(threshold, &members))]
     pub fn new_multisig(
         ctx: Context<NewMultisig>,
         threshold: u16,
-        members: Vec<Pubkey>,
+        members: Vec<Member>,
         ) -> Result<()> {
         ctx.accounts.handle(
             threshold,
@@ -28,13 +29,18 @@ pub mod multisig_demo {
         )
     }
 
-    /// Initialize a new [MultisigTransaction].
+    /// Initialize a new [MultisigTransaction]. Upon execution, the [state::Vault]
+    /// at `vault_index` signs alongside the [MultisigWallet].
+    /// The transaction expires `ttl` seconds after creation, or never if `ttl`
+    /// is zero. [None] falls back on the wallet's `default_ttl`.
     #[access_control(ctx.accounts.validate())]
     pub fn new_transaction(
         ctx: Context<NewTransaction>,
         instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
     ) -> Result<()> {
-        ctx.accounts.handle(instructions)
+        ctx.accounts.handle(instructions, vault_index, ttl)
     }
 
     /// Approve a [MultisigTransaction] for execution.
@@ -53,6 +59,15 @@ pub mod multisig_demo {
         ctx.accounts.handle_unapprove()
     }
 
+    /// Reject a [MultisigTransaction]. Once enough members reject it that the
+    /// threshold can no longer be reached, the transaction is cancelled.
+    #[access_control(ctx.accounts.validate())]
+    pub fn reject(
+        ctx: Context<Approval>,
+    ) -> Result<()> {
+        ctx.accounts.handle_reject()
+    }
+
     /// Execute a [MultisigTransaction], iff it has enough approvals and hasn't
     /// yet been executed.
     #[access_control(ctx.accounts.validate())]
@@ -69,10 +84,35 @@ pub mod multisig_demo {
         ctx.accounts.handle_change_threshold(threshold)
     }
 
+    pub fn change_min_delay(
+        ctx: Context<Administration>,
+        min_delay: u32,
+    ) -> Result<()> {
+        ctx.accounts.handle_change_min_delay(min_delay)
+    }
+
+    pub fn change_default_ttl(
+        ctx: Context<Administration>,
+        default_ttl: u32,
+    ) -> Result<()> {
+        ctx.accounts.handle_change_default_ttl(default_ttl)
+    }
+
     pub fn change_members(
         ctx: Context<Administration>,
-        members: Vec<Pubkey>,
+        members: Vec<Member>,
     ) -> Result<()> {
         ctx.accounts.handle_change_members(members)
     }
+
+    /// Close a [MultisigTransaction] that was executed, cancelled, expired, or that
+    /// belongs to a previous wallet configuration, and return its rent to the proposer.
+    #[access_control(ctx.accounts.validate())]
+    pub fn close_transaction(
+        ctx: Context<CloseTransaction>,
+    ) -> Result<()> {
+        // The `close` constraint on [CloseTransaction] does the rest.
+        ctx.accounts.handle();
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 use solana_program::pubkey::Pubkey;
+use vipers::invariant;
+use crate::error::MultisigError;
 
 /// PDA that represents an M of N multisig signer, and all relevant metadata.
 #[account]
@@ -8,17 +10,25 @@ pub struct MultisigWallet {
     /// Base used to derive.
     pub base: Pubkey,
     /// Members able to create and approve a [MultisigTransaction] owned by this
-    /// multisig.
-    pub members: Vec<Pubkey>,
-    /// At least this many members needed to approve [MultisigTransaction]
-    /// before it can be executed.
+    /// multisig, sorted by their key.
+    pub members: Vec<Member>,
+    /// At least this much total [Member.weight] needs to approve a
+    /// [MultisigTransaction] before it can be executed.
     pub threshold: u16,
+    /// Minimum number of seconds between a [MultisigTransaction] first reaching
+    /// the threshold and its execution. Only changeable through [Administration].
+    pub min_delay: u32,
+    /// Seconds until a new [MultisigTransaction] expires, unless the proposer
+    /// specifies otherwise. Zero means that transactions don't expire by default.
+    pub default_ttl: u32,
     /// To ensure uniqueness of [MultisigTransaction] PDA address.
     pub tx_nonce: u64,
-    /// This is used to make sure that a transaction created with owner set X
-    /// cannot be approved by a new owner set Y. This handles the edge cases
-    /// where a user approves a transaction and then leaves multisig membership.
-    pub member_set_seqno: u32,
+    /// Incremented by every [Administration] change, so that a transaction
+    /// created under configuration X cannot be approved or executed under a
+    /// new configuration Y. This handles the edge cases where a user approves
+    /// a transaction and then leaves multisig membership, or where the
+    /// threshold or timelock change while a transaction is pending.
+    pub config_seqno: u32,
     pub bump: u8,
 }
 
@@ -27,12 +37,86 @@ impl MultisigWallet {
         8 + // Anchor Account Discriminator
         32 + // base: Pubkey,
         4 + // members: Vec length (u32)
-        32 *  num_members + // members: 32 bytes per Pubkey
+        Member::LEN * num_members + // members: Vec<Member>
         2 + // threshold: u16
+        4 + // min_delay: u32
+        4 + // default_ttl: u32
         8 + // tx_nonce: u64
-        4 + // owner_set_seqno: u64
+        4 + // config_seqno: u32
         1 // bump
     }
+
+    /// Members are stored in sorted order, so that a member's index
+    /// (and therefore their slot in [MultisigTransaction.approved])
+    /// can be found with a binary search.
+    /// Returns the canonical (sorted) member set, or an error if there are
+    /// duplicates or members without any weight.
+    pub fn canonical_members(members: &[Member]) -> Result<Vec<Member>> {
+        invariant!(members.iter().all(|member| member.weight > 0), MultisigError::ZeroWeight);
+        let mut sorted = members.to_vec();
+        sorted.sort_by_key(|member| member.key);
+        sorted.dedup_by_key(|member| member.key);
+        invariant!(sorted.len() == members.len(), MultisigError::DuplicateMembers);
+        Ok(sorted)
+    }
+
+    /// The combined weight of `members`, which bounds the threshold.
+    pub fn total_weight(members: &[Member]) -> u64 {
+        members.iter().map(|member| member.weight as u64).sum()
+    }
+
+    /// The index of `key` among the members, if it is one.
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members.binary_search_by_key(key, |member| member.key).ok()
+    }
+
+    pub fn is_member(&self, key: &Pubkey) -> bool {
+        self.member_index(key).is_some()
+    }
+}
+
+/// A member of a [MultisigWallet], whose approval counts `weight` towards
+/// the wallet's threshold.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Member {
+    pub key: Pubkey,
+    pub weight: u16,
+}
+
+impl Member {
+    pub const LEN: usize =
+        32 + // key: Pubkey
+        2; // weight: u16
+}
+
+/// Data-less, system-owned PDAs that hold funds on behalf of a [MultisigWallet].
+/// The wallet itself is a program-owned data account, so it can't be the `from`
+/// of a system transfer. Any number of vaults can be derived per wallet.
+pub struct Vault;
+
+impl Vault {
+    pub fn find_address(multisig_wallet: &Pubkey, index: u8) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"Vault".as_ref(),
+                multisig_wallet.as_ref(),
+                &[index],
+            ],
+            &crate::ID,
+        )
+    }
+}
+
+#[macro_export]
+macro_rules! gen_vault_seeds {
+    ($multisig_wallet:expr, $index:expr, $bump:expr) => {
+        &[
+             b"Vault".as_ref(),
+             $multisig_wallet.as_ref(),
+             &[$index],
+             &[$bump],
+        ]
+    };
 }
 
 #[macro_export]
@@ -52,22 +136,37 @@ macro_rules! gen_multisig_wallet_seeds {
 pub struct MultisigTransaction {
     /// The instruction set of the transaction to be executed.
     pub instructions: Vec<Instruction>,
+    /// The [Vault] that signs alongside the [MultisigWallet] during execution.
+    pub vault_index: u8,
     /// The [MultisigWallet] account this transaction belongs to.
     pub multisig_wallet: Pubkey,
     /// Keeps track of which accounts approved the transaction, and when
     /// their last approval occurred.
     /// `approved[i]` is Some iff `[MultisigWallet.members[i]]` signed the transaction.
     pub approved: Vec<Option<i64>>,
-    /// Saved to ensure that no approvals occur on a different set of memberships.
-    pub member_set_seqno: u32,
+    /// Keeps track of which accounts rejected the transaction, and when.
+    /// `rejected[i]` is Some iff `[MultisigWallet.members[i]]` rejected the transaction.
+    /// A member cannot have both approved and rejected the same transaction.
+    pub rejected: Vec<Option<i64>>,
+    /// Saved to ensure that no approvals occur under a different wallet configuration.
+    pub config_seqno: u32,
     /// Unix timestamp at time of the [MultisigTransaction] account's creation.
     pub created_at: i64,
+    /// Unix timestamp after which the transaction can no longer be approved or executed,
+    /// or [None] if it doesn't expire.
+    pub expires_at: Option<i64>,
     /// The account that executed the [Transaction].
     pub proposer: Pubkey,
     /// The account that executed the [Transaction].
     pub executor: Option<Pubkey>,
+    /// When the transaction reached the approval threshold, which starts the
+    /// [MultisigWallet.min_delay] timelock. Cleared when approvals fall below it.
+    pub threshold_reached_at: Option<i64>,
     /// If/when the transaction was executed.
     pub executed_at: Option<i64>,
+    /// If/when enough members rejected the transaction that the threshold
+    /// can no longer be reached.
+    pub cancelled_at: Option<i64>,
 }
 
 impl MultisigTransaction {
@@ -78,14 +177,61 @@ impl MultisigTransaction {
                 .iter()
                 .map(|ix| ix.space())
                 .sum::<usize>()) + // instructions: Vec<Instruction>,
+            1 + // vault_index: u8
             32 + // multisig_wallet: Pubkey,
             4 + // approved: Vec length: u32
             (1 + 8) * num_members + // approved: Vec<Option<i64>>,
-            4 + // member_set_seqno: u64
+            4 + // rejected: Vec length: u32
+            (1 + 8) * num_members + // rejected: Vec<Option<i64>>,
+            4 + // config_seqno: u32
             8 + // created_at: i64
+            (1 + 8) + // expires_at: Option<i64>,
             32 + // proposer: Pubkey,
             (1 + 32) + // executor: Option<Pubkey>,
-            (1 + 8) // executed_at: Option<Pubkey>,
+            (1 + 8) + // threshold_reached_at: Option<i64>,
+            (1 + 8) + // executed_at: Option<i64>,
+            (1 + 8) // cancelled_at: Option<i64>,
+    }
+
+    /// The combined weight of the members who approved the transaction.
+    /// Only meaningful under the configuration the transaction was created under.
+    pub fn approved_weight(&self, multisig_wallet: &MultisigWallet) -> u64 {
+        Self::weight_of(&self.approved, multisig_wallet)
+    }
+
+    /// The combined weight of the members who rejected the transaction.
+    /// Only meaningful under the configuration the transaction was created under.
+    pub fn rejected_weight(&self, multisig_wallet: &MultisigWallet) -> u64 {
+        Self::weight_of(&self.rejected, multisig_wallet)
+    }
+
+    fn weight_of(votes: &[Option<i64>], multisig_wallet: &MultisigWallet) -> u64 {
+        votes.iter()
+            .zip(&multisig_wallet.members)
+            .filter(|(vote, _)| vote.is_some())
+            .map(|(_, member)| member.weight as u64)
+            .sum()
+    }
+
+    /// Earliest unix timestamp at which the transaction can be executed,
+    /// or [None] if it hasn't reached the approval threshold yet.
+    pub fn executable_at(&self, multisig_wallet: &MultisigWallet) -> Option<i64> {
+        self.threshold_reached_at
+            .map(|reached_at| reached_at.saturating_add(multisig_wallet.min_delay as i64))
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if now > expires_at)
+    }
+
+    /// A transaction can be closed once it can no longer be executed,
+    /// i.e. it was already executed, it was cancelled, it expired,
+    /// or it was created under a previous wallet configuration.
+    pub fn is_closeable(&self, multisig_wallet: &MultisigWallet, now: i64) -> bool {
+        self.executed_at.is_some() ||
+            self.cancelled_at.is_some() ||
+            self.is_expired(now) ||
+            self.config_seqno != multisig_wallet.config_seqno
     }
 }
 
@@ -186,15 +332,121 @@ mod tests {
             MultisigWallet::space(0),
             DISCRIMINATOR_BYTE_SIZE + MultisigWallet::default().try_to_vec().unwrap().len(),
         );
+        let wallet = MultisigWallet {
+            members: vec![Member::default()],
+            ..Default::default()
+        };
         assert_eq!(
             MultisigWallet::space(1),
-            DISCRIMINATOR_BYTE_SIZE + MultisigWallet::default().try_to_vec().unwrap().len() + std::mem::size_of::<Pubkey>(),
+            DISCRIMINATOR_BYTE_SIZE + wallet.try_to_vec().unwrap().len(),
         );
     }
 
+    fn member(byte: u8, weight: u16) -> Member {
+        Member {
+            key: Pubkey::new_from_array([byte; 32]),
+            weight,
+        }
+    }
+
+    #[test]
+    fn canonical_members_are_sorted() {
+        let a = member(1, 1);
+        let b = member(2, 3);
+        let c = member(3, 2);
+        assert_eq!(
+            MultisigWallet::canonical_members(&[c, a, b]).unwrap(),
+            vec![a, b, c],
+        );
+    }
+
+    #[test]
+    fn canonical_members_rejects_duplicates() {
+        let a = member(1, 1);
+        let b = member(2, 1);
+        // Non-adjacent duplicates
+        assert!(MultisigWallet::canonical_members(&[a, b, a]).is_err());
+        // Adjacent duplicates
+        assert!(MultisigWallet::canonical_members(&[a, a, b]).is_err());
+        // Duplicate keys with different weights
+        assert!(MultisigWallet::canonical_members(&[a, member(1, 2)]).is_err());
+    }
+
+    #[test]
+    fn canonical_members_rejects_zero_weights() {
+        assert!(MultisigWallet::canonical_members(&[member(1, 1), member(2, 0)]).is_err());
+    }
+
+    #[test]
+    fn weights_of_votes() {
+        let wallet = MultisigWallet {
+            members: vec![member(1, 1), member(2, 2), member(3, u16::MAX)],
+            ..Default::default()
+        };
+        assert_eq!(MultisigWallet::total_weight(&wallet.members), 3 + u16::MAX as u64);
+        assert_eq!(wallet.member_index(&member(2, 0).key), Some(1));
+        assert_eq!(wallet.member_index(&member(4, 0).key), None);
+        let tx = MultisigTransaction {
+            approved: vec![Some(0), None, Some(0)],
+            rejected: vec![None, Some(0), None],
+            ..Default::default()
+        };
+        assert_eq!(tx.approved_weight(&wallet), 1 + u16::MAX as u64);
+        assert_eq!(tx.rejected_weight(&wallet), 2);
+    }
+
+    #[test]
+    fn closeable_transactions() {
+        let wallet = MultisigWallet {
+            config_seqno: 1,
+            ..Default::default()
+        };
+        let pending = MultisigTransaction {
+            config_seqno: 1,
+            ..Default::default()
+        };
+        assert!(!pending.is_closeable(&wallet, 0));
+        let executed = MultisigTransaction {
+            config_seqno: 1,
+            executed_at: Some(0),
+            ..Default::default()
+        };
+        assert!(executed.is_closeable(&wallet, 0));
+        let cancelled = MultisigTransaction {
+            config_seqno: 1,
+            cancelled_at: Some(0),
+            ..Default::default()
+        };
+        assert!(cancelled.is_closeable(&wallet, 0));
+        let expiring = MultisigTransaction {
+            config_seqno: 1,
+            expires_at: Some(100),
+            ..Default::default()
+        };
+        assert!(!expiring.is_closeable(&wallet, 100));
+        assert!(expiring.is_closeable(&wallet, 101));
+        let stale = MultisigTransaction {
+            config_seqno: 0,
+            ..Default::default()
+        };
+        assert!(stale.is_closeable(&wallet, 0));
+    }
+
+    #[test]
+    fn executable_at_includes_min_delay() {
+        let wallet = MultisigWallet {
+            min_delay: 60,
+            ..Default::default()
+        };
+        let mut tx = MultisigTransaction::default();
+        assert_eq!(tx.executable_at(&wallet), None);
+        tx.threshold_reached_at = Some(1_000);
+        assert_eq!(tx.executable_at(&wallet), Some(1_060));
+    }
+
     #[test]
     fn multisig_transaction_length() {
-        // Note -- If executor and executed_at were left [None],
+        // Note -- If the Option fields were left [None],
         // the unit tests here would fail, it seems Option types are serializing
         // to one byte when they're None, but 1 + n bytes when they're Some(T), where
         // n is the length of a serialized T.
@@ -202,8 +454,11 @@ mod tests {
             MultisigTransaction::space(vec![], 0),
             DISCRIMINATOR_BYTE_SIZE +
                 MultisigTransaction {
+                    expires_at: Some(Default::default()),
+                    threshold_reached_at: Some(Default::default()),
                     executor: Some(Default::default()),
                     executed_at: Some(Default::default()),
+                    cancelled_at: Some(Default::default()),
                     ..Default::default()
                 }.try_to_vec().unwrap().len(),
         );
@@ -212,8 +467,11 @@ mod tests {
         let ix = transfer(
             &Default::default(), &Default::default(), 0);
         tx.instructions = vec![ix.clone().into()];
+        tx.expires_at = Some(Default::default());
+        tx.threshold_reached_at = Some(Default::default());
         tx.executor = Some(Default::default());
         tx.executed_at = Some(Default::default());
+        tx.cancelled_at = Some(Default::default());
         assert_eq!(
             MultisigTransaction::space(vec![ix.clone().into()], 0),
             DISCRIMINATOR_BYTE_SIZE +
@@ -0,0 +1,290 @@
+//! The [Harness] shared by the integration tests.
+#![allow(dead_code)]
+
+mod native;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::Clock;
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
+use multisig_demo::error::MultisigError;
+use multisig_demo::state::{Member, MultisigTransaction, MultisigWallet, Vault};
+use solana_program_test::{ProgramTest, ProgramTestBanksClientExt, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub const STARTING_BALANCE: u64 = LAMPORTS_PER_SOL;
+
+/// Four funded users, and the multisig wallet under test once it is created.
+pub struct Harness {
+    pub context: ProgramTestContext,
+    pub users: Vec<Keypair>,
+    pub base: Keypair,
+    pub multisig: Pubkey,
+}
+
+impl Harness {
+    pub async fn new() -> Self {
+        // Runs the BPF build under `cargo test-bpf`, and the program natively otherwise.
+        let mut program = ProgramTest::new(
+            "multisig_demo", multisig_demo::ID, Some(native::multisig_demo));
+        program.add_program("spl_memo", spl_memo::id(), Some(native::spl_memo));
+        let users: Vec<Keypair> = (0..4).map(|_| Keypair::new()).collect();
+        for user in &users {
+            program.add_account(user.pubkey(), Account {
+                lamports: STARTING_BALANCE,
+                ..Default::default()
+            });
+        }
+        let base = Keypair::new();
+        let (multisig, _) = Pubkey::find_program_address(
+            &[b"MultisigWallet".as_ref(), base.pubkey().as_ref()],
+            &multisig_demo::ID,
+        );
+        let context = program.start_with_context().await;
+        native::install_syscall_stubs();
+        Harness {
+            context,
+            users,
+            base,
+            multisig,
+        }
+    }
+
+    /// A harness with a 2 of 3 wallet of the first three users.
+    pub async fn with_wallet() -> Self {
+        let mut harness = Harness::new().await;
+        let members = harness.members(&[0, 1, 2]);
+        harness.new_multisig(2, members).await.unwrap();
+        harness
+    }
+
+    pub fn pubkeys(&self, users: &[usize]) -> Vec<Pubkey> {
+        users.iter().map(|&user| self.users[user].pubkey()).collect()
+    }
+
+    /// `users` as members with a weight of one each.
+    pub fn members(&self, users: &[usize]) -> Vec<Member> {
+        self.weighted_members(&users.iter().map(|&user| (user, 1)).collect::<Vec<_>>())
+    }
+
+    pub fn weighted_members(&self, users: &[(usize, u16)]) -> Vec<Member> {
+        users.iter()
+            .map(|&(user, weight)| Member { key: self.users[user].pubkey(), weight })
+            .collect()
+    }
+
+    /// Send `instructions` signed by `user`, who also pays the fees, and by `extra`.
+    /// Every transaction is signed over a new blockhash, so that repeating one
+    /// doesn't fail as already processed.
+    pub async fn process(
+        &mut self,
+        user: usize,
+        instructions: &[Instruction],
+        extra: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let blockhash = self.context.banks_client
+            .get_new_latest_blockhash(&self.context.last_blockhash)
+            .await
+            .unwrap();
+        self.context.last_blockhash = blockhash;
+        let payer = &self.users[user];
+        let mut signers = vec![payer];
+        signers.extend_from_slice(extra);
+        let tx = Transaction::new_signed_with_payer(
+            instructions, Some(&payer.pubkey()), &signers, blockhash);
+        self.context.banks_client
+            .process_transaction(tx)
+            .await
+            .map_err(|err| err.unwrap())
+    }
+
+    pub async fn account(&mut self, address: Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(address).await.unwrap()
+    }
+
+    pub async fn balance(&mut self, address: Pubkey) -> u64 {
+        self.account(address).await.map_or(0, |account| account.lamports)
+    }
+
+    pub async fn wallet(&mut self) -> MultisigWallet {
+        let account = self.account(self.multisig).await.unwrap();
+        MultisigWallet::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn transaction(&mut self, transaction: Pubkey) -> MultisigTransaction {
+        let account = self.account(transaction).await.unwrap();
+        MultisigTransaction::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn vault(&self, index: u8) -> Pubkey {
+        Vault::find_address(&self.multisig, index).0
+    }
+
+    pub async fn fund_vault(&mut self, lamports: u64) {
+        let transfer = system_instruction::transfer(
+            &self.users[0].pubkey(), &self.vault(0), lamports);
+        self.process(0, &[transfer], &[]).await.unwrap();
+    }
+
+    /// Create the wallet, paid for by the first user.
+    pub async fn new_multisig(&mut self, threshold: u16, members: Vec<Member>) -> Result<(), TransactionError> {
+        let ix = Instruction {
+            program_id: multisig_demo::ID,
+            accounts: multisig_demo::accounts::NewMultisig {
+                base: self.base.pubkey(),
+                payer: self.users[0].pubkey(),
+                multisig_wallet: self.multisig,
+                system_program: system_program::ID,
+            }.to_account_metas(None),
+            data: multisig_demo::instruction::NewMultisig { threshold, members }.data(),
+        };
+        let base = Keypair::from_bytes(&self.base.to_bytes()).unwrap();
+        self.process(0, &[ix], &[&base]).await
+    }
+
+    /// Propose `instructions` for the first vault to sign, returning the proposal's address.
+    pub async fn propose(&mut self, user: usize, instructions: Vec<Instruction>) -> Result<Pubkey, TransactionError> {
+        self.propose_with_ttl(user, instructions, None).await
+    }
+
+    /// [Harness::propose] with a `ttl` other than the wallet's default.
+    pub async fn propose_with_ttl(
+        &mut self,
+        user: usize,
+        instructions: Vec<Instruction>,
+        ttl: Option<u32>,
+    ) -> Result<Pubkey, TransactionError> {
+        let tx_nonce = self.wallet().await.tx_nonce;
+        let (transaction, _) = Pubkey::find_program_address(
+            &[
+                b"MultisigTransaction".as_ref(),
+                self.multisig.as_ref(),
+                tx_nonce.to_le_bytes().as_ref(),
+            ],
+            &multisig_demo::ID,
+        );
+        let ix = Instruction {
+            program_id: multisig_demo::ID,
+            accounts: multisig_demo::accounts::NewTransaction {
+                proposer: self.users[user].pubkey(),
+                multisig_wallet: self.multisig,
+                transaction,
+                system_program: system_program::ID,
+            }.to_account_metas(None),
+            data: multisig_demo::instruction::NewTransaction {
+                instructions: instructions.into_iter().map(Into::into).collect(),
+                vault_index: 0,
+                ttl,
+            }.data(),
+        };
+        self.process(user, &[ix], &[]).await?;
+        Ok(transaction)
+    }
+
+    pub fn approval_ix(&self, user: usize, transaction: Pubkey, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: multisig_demo::ID,
+            accounts: multisig_demo::accounts::Approval {
+                member: self.users[user].pubkey(),
+                multisig_wallet: self.multisig,
+                transaction,
+            }.to_account_metas(None),
+            data,
+        }
+    }
+
+    pub async fn approve(&mut self, user: usize, transaction: Pubkey) -> Result<(), TransactionError> {
+        let ix = self.approval_ix(user, transaction, multisig_demo::instruction::Approve.data());
+        self.process(user, &[ix], &[]).await
+    }
+
+    pub async fn unapprove(&mut self, user: usize, transaction: Pubkey) -> Result<(), TransactionError> {
+        let ix = self.approval_ix(user, transaction, multisig_demo::instruction::Unapprove.data());
+        self.process(user, &[ix], &[]).await
+    }
+
+    pub async fn reject(&mut self, user: usize, transaction: Pubkey) -> Result<(), TransactionError> {
+        let ix = self.approval_ix(user, transaction, multisig_demo::instruction::Reject.data());
+        self.process(user, &[ix], &[]).await
+    }
+
+    /// Execute the proposal, passing the accounts of its instructions along.
+    /// The wallet and the vault are signed for by the program.
+    pub async fn execute(&mut self, user: usize, transaction: Pubkey) -> Result<(), TransactionError> {
+        let data = self.transaction(transaction).await;
+        let vault = self.vault(data.vault_index);
+        let mut ix = self.approval_ix(user, transaction, multisig_demo::instruction::Execute.data());
+        for proposed in data.instructions {
+            for key in proposed.keys {
+                if key.pubkey == self.multisig {
+                    continue;
+                }
+                ix.accounts.push(AccountMeta {
+                    pubkey: key.pubkey,
+                    is_signer: key.is_signer && key.pubkey != vault,
+                    is_writable: key.is_writable,
+                });
+            }
+            ix.accounts.push(AccountMeta::new_readonly(proposed.program_id, false));
+        }
+        ix.accounts.push(AccountMeta::new_readonly(self.multisig, false));
+        self.process(user, &[ix], &[]).await
+    }
+
+    pub async fn close(&mut self, user: usize, transaction: Pubkey) -> Result<(), TransactionError> {
+        let proposer = self.transaction(transaction).await.proposer;
+        let ix = Instruction {
+            program_id: multisig_demo::ID,
+            accounts: multisig_demo::accounts::CloseTransaction {
+                multisig_wallet: self.multisig,
+                transaction,
+                proposer,
+            }.to_account_metas(None),
+            data: multisig_demo::instruction::CloseTransaction.data(),
+        };
+        self.process(user, &[ix], &[]).await
+    }
+
+    /// An instruction that modifies the wallet, which must be proposed and executed.
+    pub fn administration_ix(&self, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: multisig_demo::ID,
+            accounts: multisig_demo::accounts::Administration {
+                multisig_wallet: self.multisig,
+                payer: self.vault(0),
+                system_program: system_program::ID,
+            }.to_account_metas(None),
+            data,
+        }
+    }
+
+    /// Propose `ix`, approve it by `approvers` and execute it, returning the proposal.
+    pub async fn pass(&mut self, ix: Instruction, approvers: &[usize]) -> Result<Pubkey, TransactionError> {
+        let transaction = self.propose(approvers[0], vec![ix]).await?;
+        for &approver in approvers {
+            self.approve(approver, transaction).await?;
+        }
+        self.execute(approvers[0], transaction).await?;
+        Ok(transaction)
+    }
+
+    pub fn memo_ix(&self, memo: &str) -> Instruction {
+        spl_memo::build_memo(memo.as_bytes(), &[&self.multisig])
+    }
+
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+}
+
+/// The error of instruction `index` failing with `error`.
+pub fn multisig_error(index: u8, error: MultisigError) -> TransactionError {
+    TransactionError::InstructionError(index, InstructionError::Custom(ERROR_CODE_OFFSET + error as u32))
+}
@@ -0,0 +1,222 @@
+//! Runs the programs natively, in the same process as the tests.
+//!
+//! `processor!` in this version of `solana-program-test` hands the program account
+//! copies that can't change size, and fails any CPI that resizes an account, such as
+//! the `create_account` behind `#[account(init)]`. Instead, [process_instruction]
+//! serializes the accounts the way the BPF loader does, leaving room for the data to
+//! grow, and [NativeSyscallStubs] resizes the caller's accounts after a CPI the way
+//! the BPF syscall does. Under `cargo test-bpf`, the BPF build of `multisig_demo`
+//! is used instead.
+
+use std::cell::RefCell;
+use std::sync::{Arc, Once};
+use solana_bpf_loader_program::serialization::{deserialize_parameters, serialize_parameters};
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_program_runtime::timings::ExecuteTimings;
+use solana_program_runtime::{ic_msg, stable_log};
+use solana_sdk::account::{ReadableAccount, WritableAccount};
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::entrypoint::{self, ProcessInstruction, ProgramResult, SUCCESS};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::program_error::{ProgramError, UNSUPPORTED_SYSVAR};
+use solana_sdk::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_sdk::pubkey::Pubkey;
+
+thread_local! {
+    /// The context of the instruction being processed on this thread, if any.
+    static INVOKE_CONTEXT: RefCell<Option<usize>> = const { RefCell::new(None) };
+}
+
+/// Set the context of the instruction being processed, returning the previous one.
+fn set_invoke_context(ptr: Option<usize>) -> Option<usize> {
+    INVOKE_CONTEXT.with(|cell| cell.replace(ptr))
+}
+
+fn try_get_invoke_context<'a, 'b>() -> Option<&'a mut InvokeContext<'b>> {
+    let ptr = INVOKE_CONTEXT.with(|cell| *cell.borrow())?;
+    Some(unsafe { &mut *(ptr as *mut InvokeContext) })
+}
+
+fn get_invoke_context<'a, 'b>() -> &'a mut InvokeContext<'b> {
+    try_get_invoke_context().expect("no instruction is being processed")
+}
+
+/// Replace the syscall stubs of `solana-program-test`, which it installs when
+/// its first bank starts.
+pub fn install_syscall_stubs() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        set_syscall_stubs(Box::new(NativeSyscallStubs));
+    });
+}
+
+/// Runs `multisig_demo` natively, for [solana_program_test::ProgramTest::new].
+pub fn multisig_demo(
+    _first_instruction_account: usize,
+    _input: &[u8],
+    invoke_context: &mut InvokeContext,
+) -> Result<(), InstructionError> {
+    process_instruction(multisig_demo::entry, invoke_context)
+}
+
+/// Runs `spl_memo` natively, in place of the BPF build that `solana-program-test`
+/// deploys, since the BPF loader of this version can't run on recent toolchains.
+pub fn spl_memo(
+    _first_instruction_account: usize,
+    _input: &[u8],
+    invoke_context: &mut InvokeContext,
+) -> Result<(), InstructionError> {
+    process_instruction(spl_memo::processor::process_instruction, invoke_context)
+}
+
+/// The native counterpart of the BPF loader.
+fn process_instruction(
+    entrypoint: ProcessInstruction,
+    invoke_context: &mut InvokeContext,
+) -> Result<(), InstructionError> {
+    let previous = set_invoke_context(Some(invoke_context as *mut InvokeContext as usize));
+    let result = run(entrypoint, invoke_context);
+    set_invoke_context(previous);
+    result
+}
+
+fn run(
+    entrypoint: ProcessInstruction,
+    invoke_context: &mut InvokeContext,
+) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let (mut parameters, account_lengths) =
+        serialize_parameters(transaction_context, instruction_context)?;
+
+    let (program_id, account_infos, data) =
+        unsafe { entrypoint::deserialize(parameters.as_slice_mut().as_mut_ptr()) };
+    entrypoint(program_id, &account_infos, data)
+        .map_err(|err| InstructionError::from(u64::from(err)))?;
+    drop(account_infos);
+
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    deserialize_parameters(
+        transaction_context,
+        instruction_context,
+        parameters.as_slice(),
+        &account_lengths,
+        true,
+    )
+}
+
+struct NativeSyscallStubs;
+
+impl NativeSyscallStubs {
+    fn get_sysvar<T: Clone>(
+        sysvar: Result<Arc<T>, InstructionError>,
+        var_addr: *mut u8,
+    ) -> u64 {
+        match sysvar {
+            Ok(sysvar) => {
+                unsafe { *(var_addr as *mut T) = T::clone(&sysvar) };
+                SUCCESS
+            }
+            Err(_) => UNSUPPORTED_SYSVAR,
+        }
+    }
+}
+
+impl SyscallStubs for NativeSyscallStubs {
+    fn sol_log(&self, message: &str) {
+        // The tests also call into the program outside of any instruction.
+        match try_get_invoke_context() {
+            Some(invoke_context) => {
+                ic_msg!(invoke_context, "Program log: {}", message);
+            }
+            None => println!("{}", message),
+        }
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        if let Some(invoke_context) = try_get_invoke_context() {
+            stable_log::program_data(&invoke_context.get_log_collector(), fields);
+        }
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let invoke_context = get_invoke_context();
+        let transaction_context = &invoke_context.transaction_context;
+        let instruction_context = transaction_context.get_current_instruction_context()
+            .map_err(|_| ProgramError::InvalidArgument)?;
+        let caller = *instruction_context.get_program_key(transaction_context)
+            .map_err(|_| ProgramError::InvalidArgument)?;
+        let signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (instruction_accounts, program_indices) = invoke_context
+            .prepare_instruction(instruction, &signers)
+            .map_err(|err| ProgramError::try_from(err).unwrap_or(ProgramError::InvalidArgument))?;
+
+        // Pass the caller's changes on to the callee.
+        let mut writable = vec![];
+        for instruction_account in &instruction_accounts {
+            let index = instruction_account.index_in_transaction;
+            let key = invoke_context.transaction_context.get_key_of_account_at_index(index)
+                .map_err(|_| ProgramError::InvalidArgument)?;
+            let account_info = account_infos
+                .iter()
+                .position(|account_info| account_info.key == key)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let account_info = &account_infos[account_info];
+            let mut account = invoke_context.transaction_context.get_account_at_index(index)
+                .map_err(|_| ProgramError::InvalidArgument)?
+                .borrow_mut();
+            account.copy_into_owner_from_slice(account_info.owner.as_ref());
+            account.set_data_from_slice(&account_info.try_borrow_data()?);
+            account.set_lamports(account_info.lamports());
+            if instruction_account.is_writable {
+                writable.push((index, account_info));
+            }
+        }
+
+        invoke_context
+            .process_instruction(
+                &instruction.data,
+                &instruction_accounts,
+                &program_indices,
+                &mut 0,
+                &mut ExecuteTimings::default(),
+            )
+            .map_err(|err| ProgramError::try_from(err).unwrap_or(ProgramError::InvalidArgument))?;
+
+        // Pass the callee's changes back to the caller, resizing its accounts
+        // within the room that the serialization left for them to grow.
+        for (index, account_info) in writable {
+            let account = invoke_context.transaction_context.get_account_at_index(index)
+                .map_err(|_| ProgramError::InvalidArgument)?
+                .borrow();
+            **account_info.try_borrow_mut_lamports()? = account.lamports();
+            if account_info.owner != account.owner() {
+                account_info.assign(account.owner());
+            }
+            account_info.realloc(account.data().len(), false)?;
+            account_info.try_borrow_mut_data()?.copy_from_slice(account.data());
+        }
+        Ok(())
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::get_sysvar(get_invoke_context().get_sysvar_cache().get_clock(), var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::get_sysvar(get_invoke_context().get_sysvar_cache().get_epoch_schedule(), var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        Self::get_sysvar(get_invoke_context().get_sysvar_cache().get_rent(), var_addr)
+    }
+}
@@ -0,0 +1,384 @@
+//! In-process counterpart of `tests/multisig-demo.ts`, running the program with
+//! `solana-program-test`: natively under `cargo test`, or its BPF build under
+//! `cargo test-bpf`.
+
+mod common;
+
+use anchor_lang::InstructionData;
+use common::*;
+use multisig_demo::error::MultisigError;
+use multisig_demo::state::{Member, MultisigWallet};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::Signer;
+use solana_sdk::system_instruction;
+
+#[tokio::test]
+async fn cannot_create_multisig_with_invalid_threshold() {
+    let mut harness = Harness::new().await;
+    let members = harness.members(&[0, 1, 2]);
+    assert_eq!(harness.new_multisig(0, members.clone()).await.unwrap_err(),
+        multisig_error(0, MultisigError::InvalidThreshold));
+    assert_eq!(harness.new_multisig(4, members).await.unwrap_err(),
+        multisig_error(0, MultisigError::InvalidThreshold));
+    assert!(harness.account(harness.multisig).await.is_none());
+}
+
+#[tokio::test]
+async fn cannot_create_multisig_with_duplicate_members() {
+    let mut harness = Harness::new().await;
+    // Non-adjacent duplicates are also caught, since members are sorted first.
+    let members = harness.members(&[0, 1, 0]);
+    assert_eq!(harness.new_multisig(2, members).await.unwrap_err(),
+        multisig_error(0, MultisigError::DuplicateMembers));
+}
+
+#[tokio::test]
+async fn cannot_create_multisig_with_zero_weight_members() {
+    let mut harness = Harness::new().await;
+    let members = harness.weighted_members(&[(0, 1), (1, 0)]);
+    assert_eq!(harness.new_multisig(1, members).await.unwrap_err(),
+        multisig_error(0, MultisigError::ZeroWeight));
+}
+
+#[tokio::test]
+async fn unsorted_members_are_stored_sorted() {
+    let mut harness = Harness::new().await;
+    let mut members = harness.members(&[0, 1, 2]);
+    members.sort_by_key(|member| member.key);
+    members.reverse();
+    harness.new_multisig(2, members.clone()).await.unwrap();
+    let wallet = harness.wallet().await;
+    members.reverse();
+    assert_eq!(wallet.members, members);
+    assert_eq!(wallet.threshold, 2);
+    assert_eq!(wallet.base, harness.base.pubkey());
+
+    // Approvals are recorded at the member's sorted index.
+    let transaction = harness.propose(2, vec![harness.memo_ix("hello")]).await.unwrap();
+    harness.approve(2, transaction).await.unwrap();
+    let index = wallet.member_index(&harness.users[2].pubkey()).unwrap();
+    let approved = harness.transaction(transaction).await.approved;
+    assert!(approved[index].is_some());
+    assert_eq!(approved.iter().filter(|approval| approval.is_some()).count(), 1);
+}
+
+#[tokio::test]
+async fn non_members_cannot_propose_or_approve() {
+    let mut harness = Harness::with_wallet().await;
+    let memo = harness.memo_ix("hello world");
+    assert_eq!(harness.propose(3, vec![memo.clone()]).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotAMember));
+    let transaction = harness.propose(0, vec![memo]).await.unwrap();
+    assert_eq!(harness.approve(3, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotAMember));
+    assert_eq!(harness.reject(3, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotAMember));
+}
+
+#[tokio::test]
+async fn approve_unapprove_reapprove_and_execute() {
+    let mut harness = Harness::with_wallet().await;
+    let memos = vec![harness.memo_ix("hello world"), harness.memo_ix("hi mom, I'm on the blockchain")];
+    let transaction = harness.propose(0, memos).await.unwrap();
+    let wallet = harness.wallet().await;
+    let index = |harness: &Harness, user: usize| wallet.member_index(&harness.users[user].pubkey()).unwrap();
+    harness.approve(0, transaction).await.unwrap();
+    assert_eq!(harness.execute(2, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotEnoughApprovals));
+
+    harness.approve(1, transaction).await.unwrap();
+    assert_eq!(harness.approve(1, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::AlreadyApproved));
+    let data = harness.transaction(transaction).await;
+    assert!(data.approved[index(&harness, 0)].is_some());
+    assert!(data.approved[index(&harness, 1)].is_some());
+    assert!(data.approved[index(&harness, 2)].is_none());
+    assert!(data.threshold_reached_at.is_some());
+
+    harness.unapprove(1, transaction).await.unwrap();
+    assert_eq!(harness.unapprove(1, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::AlreadyUnapproved));
+    assert!(harness.transaction(transaction).await.approved[index(&harness, 1)].is_none());
+    assert_eq!(harness.execute(2, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotEnoughApprovals));
+
+    harness.approve(1, transaction).await.unwrap();
+    harness.execute(2, transaction).await.unwrap();
+    let data = harness.transaction(transaction).await;
+    assert_eq!(data.executor, Some(harness.users[2].pubkey()));
+    assert!(data.executed_at.is_some());
+    assert_eq!(harness.execute(2, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::AlreadyExecuted));
+    assert_eq!(harness.approve(2, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::AlreadyExecuted));
+}
+
+#[tokio::test]
+async fn vault_sends_sol_through_executed_transaction() {
+    let mut harness = Harness::with_wallet().await;
+    let amount = LAMPORTS_PER_SOL / 10;
+    harness.fund_vault(amount).await;
+    let recipient = harness.users[3].pubkey();
+    let transfer = system_instruction::transfer(&harness.vault(0), &recipient, amount);
+    harness.pass(transfer, &[0, 1]).await.unwrap();
+    assert_eq!(harness.balance(harness.vault(0)).await, 0);
+    assert_eq!(harness.balance(recipient).await, STARTING_BALANCE + amount);
+}
+
+#[tokio::test]
+async fn enough_rejections_cancel_a_transaction() {
+    let mut harness = Harness::with_wallet().await;
+    let transaction = harness.propose(0, vec![harness.memo_ix("rejected")]).await.unwrap();
+    harness.approve(0, transaction).await.unwrap();
+    harness.reject(1, transaction).await.unwrap();
+    assert_eq!(harness.reject(1, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::AlreadyRejected));
+    assert!(harness.transaction(transaction).await.cancelled_at.is_none());
+    // A rejection withdraws the member's approval, so two members remain to approve.
+    harness.reject(0, transaction).await.unwrap();
+    assert!(harness.transaction(transaction).await.cancelled_at.is_some());
+    assert_eq!(harness.approve(2, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::AlreadyCancelled));
+}
+
+#[tokio::test]
+async fn weighted_approvals_reach_the_threshold() {
+    let mut harness = Harness::new().await;
+    let members = harness.weighted_members(&[(0, 3), (1, 1), (2, 1)]);
+    // The threshold is bounded by the total weight, not the number of members.
+    assert_eq!(harness.new_multisig(6, members.clone()).await.unwrap_err(),
+        multisig_error(0, MultisigError::InvalidThreshold));
+    harness.new_multisig(3, members).await.unwrap();
+
+    // The lighter members can't reach the threshold together.
+    let transaction = harness.propose(1, vec![harness.memo_ix("weighted")]).await.unwrap();
+    harness.approve(1, transaction).await.unwrap();
+    harness.approve(2, transaction).await.unwrap();
+    assert_eq!(harness.execute(1, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotEnoughApprovals));
+    assert!(harness.transaction(transaction).await.threshold_reached_at.is_none());
+    // The heaviest member reaches it on their own.
+    harness.unapprove(1, transaction).await.unwrap();
+    harness.unapprove(2, transaction).await.unwrap();
+    harness.approve(0, transaction).await.unwrap();
+    assert!(harness.transaction(transaction).await.threshold_reached_at.is_some());
+    harness.execute(1, transaction).await.unwrap();
+
+    // Once the heaviest member rejects, the others can't reach the threshold anymore.
+    let transaction = harness.propose(1, vec![harness.memo_ix("rejected")]).await.unwrap();
+    harness.reject(0, transaction).await.unwrap();
+    assert!(harness.transaction(transaction).await.cancelled_at.is_some());
+}
+
+#[tokio::test]
+async fn change_threshold() {
+    let mut harness = Harness::with_wallet().await;
+    let pending = harness.propose(0, vec![harness.memo_ix("proposed under the old threshold")])
+        .await
+        .unwrap();
+    harness.approve(0, pending).await.unwrap();
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeThreshold { threshold: 1 }.data());
+    harness.pass(ix, &[0, 1]).await.unwrap();
+    let wallet = harness.wallet().await;
+    assert_eq!(wallet.threshold, 1);
+    assert_eq!(wallet.config_seqno, 1);
+
+    // The pending transaction doesn't switch to the new threshold.
+    assert_eq!(harness.execute(0, pending).await.unwrap_err(),
+        multisig_error(0, MultisigError::InvalidConfigSeqno));
+
+    // The administration instruction fails inside of the execution.
+    for threshold in [0, 4] {
+        let ix = harness.administration_ix(
+            multisig_demo::instruction::ChangeThreshold { threshold }.data());
+        assert_eq!(harness.pass(ix, &[0]).await.unwrap_err(),
+            multisig_error(0, MultisigError::InvalidThreshold));
+    }
+    let wallet = harness.wallet().await;
+    assert_eq!(wallet.threshold, 1);
+    assert_eq!(wallet.config_seqno, 1);
+}
+
+#[tokio::test]
+async fn change_members_invalidates_stale_transactions() {
+    let mut harness = Harness::with_wallet().await;
+    harness.fund_vault(LAMPORTS_PER_SOL / 10).await;
+    let stale = harness.propose(0, vec![harness.memo_ix("proposed under the old member set")])
+        .await
+        .unwrap();
+    harness.approve(0, stale).await.unwrap();
+    harness.approve(1, stale).await.unwrap();
+
+    let members = harness.members(&[0, 1, 3]);
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeMembers { members: members.clone() }.data());
+    let change = harness.pass(ix, &[0, 2]).await.unwrap();
+    let wallet = harness.wallet().await;
+    let mut sorted = members;
+    sorted.sort_by_key(|member| member.key);
+    assert_eq!(wallet.members, sorted);
+    assert_eq!(wallet.config_seqno, 1);
+
+    // Neither new nor remaining members can act on the stale transaction,
+    // even though it reached the threshold under the old member set.
+    assert_eq!(harness.approve(3, stale).await.unwrap_err(),
+        multisig_error(0, MultisigError::InvalidConfigSeqno));
+    assert_eq!(harness.unapprove(0, stale).await.unwrap_err(),
+        multisig_error(0, MultisigError::InvalidConfigSeqno));
+    assert_eq!(harness.execute(0, stale).await.unwrap_err(),
+        multisig_error(0, MultisigError::InvalidConfigSeqno));
+    assert!(harness.transaction(stale).await.executed_at.is_none());
+    // Removed members can't act on new transactions.
+    let transaction = harness.propose(0, vec![harness.memo_ix("new member set")]).await.unwrap();
+    assert_eq!(harness.approve(2, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotAMember));
+
+    // Executed and stale transactions are closed, refunding the proposer.
+    let proposer = harness.users[0].pubkey();
+    let before = harness.balance(proposer).await;
+    let rent = harness.balance(change).await + harness.balance(stale).await;
+    harness.close(3, change).await.unwrap();
+    harness.close(3, stale).await.unwrap();
+    assert!(harness.account(change).await.is_none());
+    assert!(harness.account(stale).await.is_none());
+    assert_eq!(harness.balance(proposer).await, before + rent);
+    assert_eq!(harness.close(3, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::NotCloseable));
+}
+
+#[tokio::test]
+async fn change_members_below_threshold_fails() {
+    let mut harness = Harness::with_wallet().await;
+    let members = harness.members(&[0]);
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeMembers { members }.data());
+    assert_eq!(harness.pass(ix, &[0, 1]).await.unwrap_err(),
+        multisig_error(0, MultisigError::TooFewMembers));
+    assert_eq!(harness.wallet().await.members.len(), 3);
+}
+
+#[tokio::test]
+async fn change_members_reallocates_the_wallet() {
+    let mut harness = Harness::with_wallet().await;
+    let amount = LAMPORTS_PER_SOL / 10;
+    harness.fund_vault(amount).await;
+    let rent = harness.context.banks_client.get_rent().await.unwrap();
+
+    // Growing the wallet is paid for by the vault.
+    let before = harness.account(harness.multisig).await.unwrap();
+    let members = harness.members(&[0, 1, 2, 3]);
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeMembers { members }.data());
+    harness.pass(ix, &[0, 1]).await.unwrap();
+    let after = harness.account(harness.multisig).await.unwrap();
+    assert_eq!(after.data.len(), MultisigWallet::space(4));
+    assert_eq!(after.data.len(), before.data.len() + Member::LEN);
+    assert_eq!(after.lamports, rent.minimum_balance(after.data.len()));
+    let rent_paid = after.lamports - before.lamports;
+    assert!(rent_paid > 0);
+    assert_eq!(harness.balance(harness.vault(0)).await, amount - rent_paid);
+    assert_eq!(harness.wallet().await.members.len(), 4);
+
+    // Shrinking it refunds the vault.
+    let before = after;
+    let vault_before = harness.balance(harness.vault(0)).await;
+    let members = harness.members(&[0, 1]);
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeMembers { members }.data());
+    harness.pass(ix, &[0, 1]).await.unwrap();
+    let after = harness.account(harness.multisig).await.unwrap();
+    assert_eq!(after.data.len(), before.data.len() - 2 * Member::LEN);
+    assert_eq!(after.lamports, rent.minimum_balance(after.data.len()));
+    let refund = before.lamports - after.lamports;
+    assert!(refund > 0);
+    assert_eq!(harness.balance(harness.vault(0)).await, vault_before + refund);
+    assert_eq!(harness.wallet().await.members.len(), 2);
+}
+
+#[tokio::test]
+async fn shrinking_the_wallet_with_an_unfunded_vault_keeps_the_excess_rent() {
+    let mut harness = Harness::with_wallet().await;
+    let before = harness.account(harness.multisig).await.unwrap();
+    // The refund is less than the rent-exempt minimum of the empty vault,
+    // which therefore can't take it.
+    let members = harness.members(&[0, 1]);
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeMembers { members }.data());
+    harness.pass(ix, &[0, 1]).await.unwrap();
+    let after = harness.account(harness.multisig).await.unwrap();
+    assert_eq!(after.data.len(), before.data.len() - Member::LEN);
+    assert_eq!(after.lamports, before.lamports);
+    assert_eq!(harness.balance(harness.vault(0)).await, 0);
+    assert_eq!(harness.wallet().await.members.len(), 2);
+}
+
+#[tokio::test]
+async fn timelock_delays_execution() {
+    let mut harness = Harness::with_wallet().await;
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeMinDelay { min_delay: 60 }.data());
+    harness.pass(ix, &[0, 1]).await.unwrap();
+    assert_eq!(harness.wallet().await.min_delay, 60);
+
+    let transaction = harness.propose(0, vec![harness.memo_ix("timelocked")]).await.unwrap();
+    harness.approve(0, transaction).await.unwrap();
+    harness.approve(1, transaction).await.unwrap();
+    assert_eq!(harness.execute(0, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::TimelockNotElapsed));
+    harness.advance_clock(60).await;
+    harness.execute(0, transaction).await.unwrap();
+}
+
+#[tokio::test]
+async fn falling_below_the_threshold_restarts_the_timelock() {
+    let mut harness = Harness::with_wallet().await;
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeMinDelay { min_delay: 60 }.data());
+    harness.pass(ix, &[0, 1]).await.unwrap();
+
+    let transaction = harness.propose(0, vec![harness.memo_ix("timelocked")]).await.unwrap();
+    harness.approve(0, transaction).await.unwrap();
+    harness.approve(1, transaction).await.unwrap();
+    harness.advance_clock(60).await;
+    harness.unapprove(1, transaction).await.unwrap();
+    assert!(harness.transaction(transaction).await.threshold_reached_at.is_none());
+
+    // Reaching the threshold again starts a new timelock.
+    harness.approve(1, transaction).await.unwrap();
+    assert_eq!(harness.execute(0, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::TimelockNotElapsed));
+
+    // As does a rejection that withdraws an approval.
+    harness.advance_clock(30).await;
+    harness.reject(1, transaction).await.unwrap();
+    assert!(harness.transaction(transaction).await.threshold_reached_at.is_none());
+    harness.approve(2, transaction).await.unwrap();
+    harness.advance_clock(30).await;
+    assert_eq!(harness.execute(0, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::TimelockNotElapsed));
+    harness.advance_clock(30).await;
+    harness.execute(0, transaction).await.unwrap();
+}
+
+#[tokio::test]
+async fn expired_transactions_cannot_be_approved() {
+    let mut harness = Harness::with_wallet().await;
+    let ix = harness.administration_ix(
+        multisig_demo::instruction::ChangeDefaultTtl { default_ttl: 60 }.data());
+    harness.pass(ix, &[0, 1]).await.unwrap();
+
+    let transaction = harness.propose(0, vec![harness.memo_ix("expiring")]).await.unwrap();
+    // A TTL of zero overrides the default, and never expires.
+    let lasting = harness.propose_with_ttl(0, vec![harness.memo_ix("lasting")], Some(0))
+        .await
+        .unwrap();
+    assert_eq!(harness.transaction(lasting).await.expires_at, None);
+    harness.approve(0, transaction).await.unwrap();
+    harness.advance_clock(61).await;
+    assert_eq!(harness.approve(1, transaction).await.unwrap_err(),
+        multisig_error(0, MultisigError::Expired));
+    harness.approve(1, lasting).await.unwrap();
+    // Expired transactions can be closed.
+    harness.close(0, transaction).await.unwrap();
+}
@@ -0,0 +1,320 @@
+//! Property-based tests driving random sequences of proposals, approvals,
+//! rejections and executions against the program, in-process like `multisig_demo.rs`.
+//! The expected outcome of each step is derived from the state before it,
+//! and the wallet's invariants are asserted after every step.
+
+mod common;
+
+use anchor_lang::InstructionData;
+use common::*;
+use multisig_demo::state::{Member, MultisigTransaction, MultisigWallet};
+use proptest::prelude::*;
+use proptest::sample::Index;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+/// The number of users funded by the [Harness].
+const NUM_USERS: usize = 4;
+/// The heaviest generated member weight.
+const MAX_WEIGHT: u16 = 3;
+/// Thresholds range up to just above the heaviest possible wallet.
+const MAX_THRESHOLD: u16 = NUM_USERS as u16 * MAX_WEIGHT + 1;
+
+/// The wallet that a run starts with. Invalid configurations must be
+/// rejected, after which the run falls back on a 2 of 3 wallet.
+#[derive(Clone, Debug)]
+struct Setup {
+    threshold: u16,
+    members: Vec<(usize, u16)>,
+}
+
+#[derive(Clone, Debug)]
+enum Proposal {
+    Memo,
+    ChangeThreshold(u16),
+    ChangeMembers(Vec<(usize, u16)>),
+}
+
+/// A step of a run. Steps on proposals pick one of the proposals so far,
+/// and are skipped if there are none yet.
+#[derive(Clone, Debug)]
+enum Step {
+    Propose { user: usize, proposal: Proposal },
+    Approve { user: usize, proposal: Index },
+    Unapprove { user: usize, proposal: Index },
+    Reject { user: usize, proposal: Index },
+    Execute { user: usize, proposal: Index },
+}
+
+fn members() -> impl Strategy<Value = Vec<(usize, u16)>> {
+    // Duplicates and zero weights are generated on purpose.
+    prop::collection::vec((0..NUM_USERS, 0..=MAX_WEIGHT), 0..=NUM_USERS)
+}
+
+fn setup() -> impl Strategy<Value = Setup> {
+    (0..=MAX_THRESHOLD, members())
+        .prop_map(|(threshold, members)| Setup { threshold, members })
+}
+
+fn proposal() -> impl Strategy<Value = Proposal> {
+    prop_oneof![
+        Just(Proposal::Memo),
+        (0..=MAX_THRESHOLD).prop_map(Proposal::ChangeThreshold),
+        members().prop_map(Proposal::ChangeMembers),
+    ]
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    let user = 0..NUM_USERS;
+    prop_oneof![
+        (user.clone(), proposal()).prop_map(|(user, proposal)| Step::Propose { user, proposal }),
+        (user.clone(), any::<Index>()).prop_map(|(user, proposal)| Step::Approve { user, proposal }),
+        (user.clone(), any::<Index>()).prop_map(|(user, proposal)| Step::Unapprove { user, proposal }),
+        (user.clone(), any::<Index>()).prop_map(|(user, proposal)| Step::Reject { user, proposal }),
+        (user, any::<Index>()).prop_map(|(user, proposal)| Step::Execute { user, proposal }),
+    ]
+}
+
+fn is_valid_member_set(members: &[Member], threshold: u16) -> bool {
+    MultisigWallet::canonical_members(members).is_ok()
+        && MultisigWallet::total_weight(members) >= threshold as u64
+}
+
+fn sorted(members: &[Member]) -> Vec<Member> {
+    let mut sorted = members.to_vec();
+    sorted.sort_by_key(|member| member.key);
+    sorted
+}
+
+/// The invariants that must hold between any two instructions.
+fn assert_invariants(wallet: &MultisigWallet, transactions: &[MultisigTransaction]) {
+    assert!(wallet.threshold >= 1, "threshold of zero: {:?}", wallet);
+    assert!(wallet.threshold as u64 <= MultisigWallet::total_weight(&wallet.members),
+        "threshold above the total weight: {:?}", wallet);
+    assert!(wallet.members.windows(2).all(|pair| pair[0].key < pair[1].key),
+        "members not unique and sorted: {:?}", wallet);
+    assert!(wallet.members.iter().all(|member| member.weight > 0),
+        "member without weight: {:?}", wallet);
+    for tx in transactions {
+        assert_eq!(tx.approved.len(), tx.rejected.len());
+        if tx.config_seqno == wallet.config_seqno {
+            // Approvals are indexed by the current members.
+            assert_eq!(tx.approved.len(), wallet.members.len());
+            // The timelock runs exactly while the approvals reach the threshold.
+            assert_eq!(tx.threshold_reached_at.is_some(),
+                tx.approved_weight(wallet) >= wallet.threshold as u64,
+                "timelock out of sync with the approvals: {:?}", tx);
+        }
+        assert!(tx.approved.iter().zip(&tx.rejected)
+            .all(|(approved, rejected)| approved.is_none() || rejected.is_none()),
+            "member both approved and rejected: {:?}", tx);
+        assert_eq!(tx.executed_at.is_some(), tx.executor.is_some());
+        assert!(tx.executed_at.is_none() || tx.cancelled_at.is_none(),
+            "executed a cancelled transaction: {:?}", tx);
+    }
+}
+
+struct Run {
+    harness: Harness,
+    proposals: Vec<(Pubkey, Proposal)>,
+}
+
+impl Run {
+    async fn new(setup: Setup) -> Self {
+        let mut harness = Harness::new().await;
+        let members = harness.weighted_members(&setup.members);
+        let valid = setup.threshold > 0 && is_valid_member_set(&members, setup.threshold);
+        let created = harness.new_multisig(setup.threshold, members.clone()).await;
+        assert_eq!(created.is_ok(), valid, "created {:?}: {:?}", setup, created);
+        if created.is_err() {
+            let members = harness.members(&[0, 1, 2]);
+            harness.new_multisig(2, members).await.unwrap();
+        } else {
+            let wallet = harness.wallet().await;
+            assert_eq!(wallet.members, sorted(&members));
+            assert_eq!(wallet.threshold, setup.threshold);
+        }
+        // Pays for the wallet to grow when members are added.
+        harness.fund_vault(LAMPORTS_PER_SOL / 10).await;
+        Run { harness, proposals: vec![] }
+    }
+
+    async fn transactions(&mut self) -> Vec<MultisigTransaction> {
+        let mut transactions = vec![];
+        for (address, _) in self.proposals.clone() {
+            transactions.push(self.harness.transaction(address).await);
+        }
+        transactions
+    }
+
+    fn instruction(&self, proposal: &Proposal) -> Instruction {
+        match proposal {
+            Proposal::Memo => self.harness.memo_ix("random state transition"),
+            Proposal::ChangeThreshold(threshold) => self.harness.administration_ix(
+                multisig_demo::instruction::ChangeThreshold { threshold: *threshold }.data()),
+            Proposal::ChangeMembers(members) => self.harness.administration_ix(
+                multisig_demo::instruction::ChangeMembers { members: self.harness.weighted_members(members) }.data()),
+        }
+    }
+
+    async fn propose(&mut self, user: usize, proposal: Proposal) {
+        let before = self.harness.wallet().await;
+        let member = before.is_member(&self.harness.users[user].pubkey());
+        let ix = self.instruction(&proposal);
+        let result = self.harness.propose(user, vec![ix]).await;
+        assert_eq!(result.is_ok(), member, "proposal by {}: {:?}", user, result);
+        let after = self.harness.wallet().await;
+        match result {
+            Ok(address) => {
+                assert_eq!(after.tx_nonce, before.tx_nonce + 1);
+                let tx = self.harness.transaction(address).await;
+                assert_eq!(tx.config_seqno, before.config_seqno);
+                assert_eq!(tx.approved, vec![None; before.members.len()]);
+                assert_eq!(tx.rejected, vec![None; before.members.len()]);
+                self.proposals.push((address, proposal));
+            }
+            Err(_) => assert_eq!(after, before),
+        }
+    }
+
+    /// Apply one of the instructions checked by `Approval::validate`, asserting
+    /// that it succeeds exactly when `expected` holds for the state before it,
+    /// and that a failure leaves all state untouched.
+    async fn approval(
+        &mut self,
+        step: &Step,
+        user: usize,
+        proposal: Index,
+        expected: impl Fn(&MultisigWallet, &MultisigTransaction, usize) -> bool,
+    ) -> Option<(MultisigWallet, MultisigTransaction, MultisigTransaction)> {
+        if self.proposals.is_empty() {
+            return None;
+        }
+        let (address, _) = self.proposals[proposal.index(self.proposals.len())].clone();
+        let wallet = self.harness.wallet().await;
+        let before = self.harness.transaction(address).await;
+        let member_idx = wallet.member_index(&self.harness.users[user].pubkey());
+        let valid = match member_idx {
+            Some(member_idx) => before.config_seqno == wallet.config_seqno
+                && before.executed_at.is_none()
+                && before.cancelled_at.is_none()
+                && expected(&wallet, &before, member_idx),
+            None => false,
+        };
+        let result = match step {
+            Step::Approve { .. } => self.harness.approve(user, address).await,
+            Step::Unapprove { .. } => self.harness.unapprove(user, address).await,
+            Step::Reject { .. } => self.harness.reject(user, address).await,
+            Step::Execute { .. } => self.harness.execute(user, address).await,
+            Step::Propose { .. } => unreachable!(),
+        };
+        assert_eq!(result.is_ok(), valid, "{:?}: {:?}", step, result);
+        let after = self.harness.transaction(address).await;
+        if result.is_err() {
+            assert_eq!(after, before);
+            assert_eq!(self.harness.wallet().await, wallet);
+            return None;
+        }
+        Some((wallet, before, after))
+    }
+
+    async fn step(&mut self, step: Step) {
+        match step.clone() {
+            Step::Propose { user, proposal } => self.propose(user, proposal).await,
+            Step::Approve { user, proposal } => {
+                let approved = self.approval(&step, user, proposal,
+                    |_, tx, member_idx| tx.approved[member_idx].is_none()).await;
+                if let Some((wallet, _, tx)) = approved {
+                    let member_idx = wallet.member_index(&self.harness.users[user].pubkey()).unwrap();
+                    assert!(tx.approved[member_idx].is_some());
+                    assert!(tx.rejected[member_idx].is_none());
+                }
+            }
+            Step::Unapprove { user, proposal } => {
+                let unapproved = self.approval(&step, user, proposal,
+                    |_, tx, member_idx| tx.approved[member_idx].is_some()).await;
+                if let Some((wallet, _, tx)) = unapproved {
+                    let member_idx = wallet.member_index(&self.harness.users[user].pubkey()).unwrap();
+                    assert!(tx.approved[member_idx].is_none());
+                }
+            }
+            Step::Reject { user, proposal } => {
+                let rejected = self.approval(&step, user, proposal,
+                    |_, tx, member_idx| tx.rejected[member_idx].is_none()).await;
+                if let Some((wallet, _, tx)) = rejected {
+                    let member_idx = wallet.member_index(&self.harness.users[user].pubkey()).unwrap();
+                    assert!(tx.approved[member_idx].is_none());
+                    assert!(tx.rejected[member_idx].is_some());
+                    let remaining_weight = MultisigWallet::total_weight(&wallet.members) - tx.rejected_weight(&wallet);
+                    let cancelled = remaining_weight < wallet.threshold as u64;
+                    assert_eq!(tx.cancelled_at.is_some(), cancelled);
+                }
+            }
+            Step::Execute { user, proposal } => {
+                if self.proposals.is_empty() {
+                    return;
+                }
+                let (_, proposed) = self.proposals[proposal.index(self.proposals.len())].clone();
+                let new_members = match &proposed {
+                    Proposal::ChangeMembers(members) => self.harness.weighted_members(members),
+                    _ => vec![],
+                };
+                let executed = self.approval(&step, user, proposal, |wallet, tx, _| {
+                    tx.approved_weight(wallet) >= wallet.threshold as u64 && match &proposed {
+                        Proposal::Memo => true,
+                        Proposal::ChangeThreshold(threshold) =>
+                            *threshold > 0
+                                && *threshold as u64 <= MultisigWallet::total_weight(&wallet.members),
+                        Proposal::ChangeMembers(_) =>
+                            is_valid_member_set(&new_members, wallet.threshold),
+                    }
+                }).await;
+                if let Some((before, _, tx)) = executed {
+                    assert_eq!(tx.executor, Some(self.harness.users[user].pubkey()));
+                    assert!(tx.executed_at.is_some());
+                    let after = self.harness.wallet().await;
+                    match proposed {
+                        Proposal::Memo => assert_eq!(after, before),
+                        Proposal::ChangeThreshold(threshold) => {
+                            assert_eq!(after.threshold, threshold);
+                            assert_eq!(after.config_seqno, before.config_seqno + 1);
+                        }
+                        Proposal::ChangeMembers(_) => {
+                            assert_eq!(after.members, sorted(&new_members));
+                            assert_eq!(after.config_seqno, before.config_seqno + 1);
+                        }
+                    }
+                }
+            }
+        }
+        let wallet = self.harness.wallet().await;
+        let transactions = self.transactions().await;
+        assert_invariants(&wallet, &transactions);
+    }
+}
+
+proptest! {
+    // Every case starts a new bank, and every step waits for a new blockhash.
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn random_state_transitions_preserve_invariants(
+        setup in setup(),
+        steps in prop::collection::vec(step(), 1..24),
+    ) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let mut run = Run::new(setup).await;
+            let wallet = run.harness.wallet().await;
+            assert_invariants(&wallet, &[]);
+            for step in steps {
+                run.step(step).await;
+            }
+        });
+    }
+}
@@ -1,9 +1,17 @@
 use anyhow::{anyhow, Result};
 use clap::parser::ArgMatches;
+use clap::{Arg, Args, Command, FromArgMatches};
+use solana_clap_v3_utils::fee_payer::{fee_payer_arg, FEE_PAYER_ARG};
+use solana_clap_v3_utils::input_parsers::{pubkeys_sigs_of, value_of};
 use solana_clap_v3_utils::keypair::signer_from_path;
+use solana_clap_v3_utils::nonce::{NonceArgs, NONCE_ARG, NONCE_AUTHORITY_ARG};
+use solana_clap_v3_utils::offline::{ArgsConfig, OfflineArgs as _, BLOCKHASH_ARG,
+    DUMP_TRANSACTION_MESSAGE, SIGNER_ARG, SIGN_ONLY_ARG};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
-use solana_sdk::signature::Signer;
+use solana_sdk::signature::{Signature, Signer};
 use clap::Parser;
 use solana_cli_config::Config;
 
@@ -32,6 +40,29 @@ impl UrlArg {
     }
 }
 
+/// Put this (flattened) at the top level of a Clap CLI made with the Derive API to add the
+/// `--commitment` CLI arg as it functions in the official Solana CLI.
+/// This is the commitment level that state is read at, and that sent transactions
+/// are waited for, otherwise defaulting to the Solana CLI config file.
+#[derive(Debug, Parser)]
+pub struct CommitmentArg {
+    /// Return information at the selected commitment level, and wait for sent
+    /// transactions to reach it.
+    #[clap(long, global = true, possible_values = ["processed", "confirmed", "finalized"])]
+    pub commitment: Option<String>,
+}
+
+impl CommitmentArg {
+    pub fn resolve(&self, config: Option<&Config>) -> Result<CommitmentConfig> {
+        let commitment = match (&self.commitment, config) {
+            (Some(commitment), _) => commitment.clone(),
+            (None, Some(config)) => config.commitment.clone(),
+            (None, None) => get_solana_cli_config()?.commitment,
+        };
+        Ok(CommitmentConfig::from_str(&commitment)?)
+    }
+}
+
 /// Put this (flattened) at the top level of a Clap CLI made with the Derive API to add the
 /// `-k/--keypair` CLI arg as it functions in the Solana CLI.
 /// This allows for manual specification of a signing keypair,
@@ -68,6 +99,111 @@ impl KeypairArg {
     }
 }
 
+/// Put this (flattened) at the top level of a Clap CLI made with the Derive API to add the
+/// transaction args of the Solana CLI: `--fee-payer`, the offline signing args
+/// `--sign-only`, `--blockhash`, `--signer PUBKEY=SIGNATURE` and `--dump-transaction-message`,
+/// and the durable nonce args `--nonce` and `--nonce-authority`.
+/// The args are global, so they can also be passed after the subcommand.
+/// They are also visible to [signer_from_path], which resolves a pubkey signer
+/// to its `--signer` presigner, or to a null signer with `--sign-only`.
+#[derive(Debug, Default)]
+pub struct TransactionArgs {
+    pub fee_payer: Option<String>,
+    pub sign_only: bool,
+    pub dump_transaction_message: bool,
+    pub blockhash: Option<Hash>,
+    pub signers: Vec<(Pubkey, Signature)>,
+    pub nonce: Option<Pubkey>,
+    pub nonce_authority: Option<String>,
+}
+
+impl FromArgMatches for TransactionArgs {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        Ok(Self {
+            fee_payer: matches.value_of(FEE_PAYER_ARG.name).map(String::from),
+            sign_only: matches.is_present(SIGN_ONLY_ARG.name),
+            dump_transaction_message: matches.is_present(DUMP_TRANSACTION_MESSAGE.name),
+            blockhash: value_of(matches, BLOCKHASH_ARG.name),
+            signers: pubkeys_sigs_of(matches, SIGNER_ARG.name).unwrap_or_default(),
+            nonce: value_of(matches, NONCE_ARG.name),
+            nonce_authority: matches.value_of(NONCE_AUTHORITY_ARG.name).map(String::from),
+        })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
+
+impl Args for TransactionArgs {
+    fn augment_args(cmd: Command<'_>) -> Command<'_> {
+        struct GlobalArgsConfig;
+        impl ArgsConfig for GlobalArgsConfig {
+            fn blockhash_arg<'a>(&self, arg: Arg<'a>) -> Arg<'a> {
+                arg.global(true)
+            }
+            fn sign_only_arg<'a>(&self, arg: Arg<'a>) -> Arg<'a> {
+                arg.global(true)
+            }
+            fn signer_arg<'a>(&self, arg: Arg<'a>) -> Arg<'a> {
+                arg.global(true)
+            }
+            fn dump_transaction_message_arg<'a>(&self, arg: Arg<'a>) -> Arg<'a> {
+                arg.global(true)
+            }
+        }
+        cmd.arg(fee_payer_arg().global(true))
+            .offline_args_config(&GlobalArgsConfig)
+            .nonce_args(true)
+    }
+
+    fn augment_args_for_update(cmd: Command<'_>) -> Command<'_> {
+        Self::augment_args(cmd)
+    }
+}
+
+impl TransactionArgs {
+    /// Resolve the `--fee-payer` signer, if any.
+    /// Callers default to the `-k/--keypair` signer otherwise.
+    pub fn resolve_fee_payer(
+        &self,
+        matches: &ArgMatches,
+    ) -> Result<Option<Box<dyn Signer>>> {
+        self.fee_payer
+            .as_ref()
+            .map(|path| parse_signer(matches, path))
+            .transpose()
+    }
+
+    /// Fail if any of the offline signing or durable nonce args were given,
+    /// for `command`s that can only send their transactions right away.
+    pub fn ensure_online(&self, command: &str) -> Result<()> {
+        let offline = self.sign_only
+            || self.dump_transaction_message
+            || self.blockhash.is_some()
+            || !self.signers.is_empty()
+            || self.nonce.is_some()
+            || self.nonce_authority.is_some();
+        if offline {
+            return Err(anyhow!("{} doesn't support offline signing or durable nonces", command));
+        }
+        Ok(())
+    }
+
+    /// Resolve the `--nonce-authority` signer, if any.
+    /// Callers default to the `-k/--keypair` signer otherwise.
+    pub fn resolve_nonce_authority(
+        &self,
+        matches: &ArgMatches,
+    ) -> Result<Option<Box<dyn Signer>>> {
+        self.nonce_authority
+            .as_ref()
+            .map(|path| parse_signer(matches, path))
+            .transpose()
+    }
+}
+
 /// Parses [solana_sdk::pubkey::Pubkey] from a string.
 pub fn pubkey_arg(pubkey: &str) -> Result<Pubkey> {
     Pubkey::from_str(pubkey).map_err(
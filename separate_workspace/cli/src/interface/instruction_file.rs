@@ -0,0 +1,173 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+/// An instruction as written in a proposal file, e.g. in JSON:
+/// ```json
+/// {
+///   "program_id": "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+///   "accounts": [
+///     { "pubkey": "<vault address>", "is_signer": true, "is_writable": false }
+///   ],
+///   "data": { "base64": "aGVsbG8=" }
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InstructionSpec {
+    pub program_id: String,
+    #[serde(default)]
+    pub accounts: Vec<AccountMetaSpec>,
+    pub data: InstructionData,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountMetaSpec {
+    pub pubkey: String,
+    #[serde(default)]
+    pub is_signer: bool,
+    #[serde(default)]
+    pub is_writable: bool,
+}
+
+/// Serialized instruction data, tagged with its encoding.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstructionData {
+    Base58(String),
+    Base64(String),
+    Hex(String),
+}
+
+impl InstructionData {
+    pub fn decode(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            InstructionData::Base58(s) => bs58::decode(s).into_vec()?,
+            InstructionData::Base64(s) => base64::decode(s)?,
+            InstructionData::Hex(s) => hex::decode(s.trim_start_matches("0x"))?,
+        })
+    }
+}
+
+impl InstructionSpec {
+    pub fn to_instruction(&self) -> Result<Instruction> {
+        let accounts = self.accounts
+            .iter()
+            .map(|meta| Ok(AccountMeta {
+                pubkey: parse_pubkey(&meta.pubkey)?,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            }))
+            .collect::<Result<Vec<AccountMeta>>>()?;
+        Ok(Instruction {
+            program_id: parse_pubkey(&self.program_id)?,
+            accounts,
+            data: self.data.decode()?,
+        })
+    }
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey> {
+    Pubkey::from_str(s).map_err(|e| anyhow!("invalid pubkey {}: {}", s, e))
+}
+
+/// Read a list of instructions from a JSON or YAML file.
+/// Files ending in `.yaml` or `.yml` are parsed as YAML, anything else as JSON.
+pub fn read_instructions(path: &Path) -> Result<Vec<Instruction>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+    let is_yaml = matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yaml") | Some("yml")
+    );
+    let specs: Vec<InstructionSpec> = if is_yaml {
+        serde_yaml::from_str(&contents)?
+    } else {
+        serde_json::from_str(&contents)?
+    };
+    if specs.is_empty() {
+        return Err(anyhow!("{} contains no instructions", path.display()));
+    }
+    specs.iter().map(InstructionSpec::to_instruction).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use super::*;
+
+    const MEMO: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+    /// Write `contents` to a temporary file whose name ends in `suffix`.
+    fn write_file(suffix: &str, contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    fn decode(data: &str) -> Result<Vec<u8>> {
+        serde_json::from_str::<InstructionData>(data)?.decode()
+    }
+
+    #[test]
+    fn decode_each_encoding() {
+        assert_eq!(decode(r#"{ "base58": "Cn8eVZg" }"#).unwrap(), b"hello");
+        assert_eq!(decode(r#"{ "base64": "aGVsbG8=" }"#).unwrap(), b"hello");
+        assert_eq!(decode(r#"{ "hex": "68656c6c6f" }"#).unwrap(), b"hello");
+        assert_eq!(decode(r#"{ "hex": "0x68656c6c6f" }"#).unwrap(), b"hello");
+        assert!(decode(r#"{ "base64": "not base64!" }"#).is_err());
+        assert!(decode(r#"{ "hex": "0xzz" }"#).is_err());
+        assert!(decode(r#"{ "base32": "NBSWY3DP" }"#).is_err());
+    }
+
+    #[test]
+    fn read_json_and_yaml() {
+        let account = Pubkey::new_unique();
+        let expected = vec![Instruction {
+            program_id: Pubkey::from_str(MEMO).unwrap(),
+            accounts: vec![AccountMeta::new_readonly(account, true)],
+            data: b"hello".to_vec(),
+        }];
+        let json = write_file(".json", &format!(r#"[{{
+            "program_id": "{}",
+            "accounts": [{{ "pubkey": "{}", "is_signer": true }}],
+            "data": {{ "base64": "aGVsbG8=" }}
+        }}]"#, MEMO, account));
+        assert_eq!(read_instructions(json.path()).unwrap(), expected);
+        for suffix in [".yaml", ".yml"] {
+            let yaml = write_file(suffix, &format!(
+                "- program_id: {}\n  accounts:\n    - pubkey: {}\n      is_signer: true\n  data:\n    hex: 68656c6c6f\n",
+                MEMO, account));
+            assert_eq!(read_instructions(yaml.path()).unwrap(), expected);
+        }
+        // Anything but `.yaml` and `.yml` is read as JSON.
+        let yaml = write_file(".txt", &format!("- program_id: {}\n  data:\n    hex: ''\n", MEMO));
+        assert!(read_instructions(yaml.path()).is_err());
+    }
+
+    #[test]
+    fn reject_malformed_files() {
+        let read = |contents: &str| read_instructions(write_file(".json", contents).path());
+        let err = read(r#"[{ "program_id": "not a pubkey", "data": { "hex": "" } }]"#).unwrap_err();
+        assert!(err.to_string().contains("invalid pubkey not a pubkey"));
+        let err = read(&format!(
+            r#"[{{ "program_id": "{}", "accounts": [{{ "pubkey": "bad" }}], "data": {{ "hex": "" }} }}]"#,
+            MEMO)).unwrap_err();
+        assert!(err.to_string().contains("invalid pubkey bad"));
+        let err = read(&format!(r#"[{{ "program_id": "{}", "data": {{ "base32": "" }} }}]"#, MEMO))
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown variant `base32`"));
+        let err = read(&format!(r#"[{{ "program_id": "{}" }}]"#, MEMO)).unwrap_err();
+        assert!(err.to_string().contains("missing field `data`"));
+        let err = read(&format!(
+            r#"[{{ "program_id": "{}", "data": {{ "hex": "" }}, "signer": true }}]"#, MEMO)).unwrap_err();
+        assert!(err.to_string().contains("unknown field `signer`"));
+        assert!(read("[]").unwrap_err().to_string().contains("contains no instructions"));
+        assert!(read_instructions(Path::new("/nonexistent/instructions.json")).is_err());
+    }
+}
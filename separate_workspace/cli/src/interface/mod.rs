@@ -1,13 +1,28 @@
-use anyhow::Result;
-use clap::{ArgMatches, Parser, IntoApp};
+use std::cell::Cell;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{anyhow, Result};
+use clap::{ArgMatches, Parser};
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
+use solana_sdk::signature::{Keypair, Presigner, Signature};
 use solana_sdk::signer::Signer;
-use anchor_client::anchor_lang::AccountDeserialize;
-use multisig_demo::state::MultisigTransaction;
-use multisig_demo_sdk::{find_multisig_wallet_address, MultisigMember, new_multisig_rpc};
-use crate::config::{UrlArg, KeypairArg, pubkey_or_signer_path, pubkey_arg};
+use solana_sdk::transaction::Transaction;
+use indicatif::ProgressBar;
+use anchor_client::anchor_lang::{AccountDeserialize, InstructionData};
+use multisig_demo::state::{MultisigTransaction, MultisigWallet};
+use multisig_demo_sdk::{fetch_transaction, fetch_wallet, fetch_wallet_transactions, find_multisig_wallet_address,
+    find_vault_address, DecoderRegistry, MultisigMember, new_multisig_ix, TransactionStatus};
+use multisig_demo_sdk::{approval_ix, build_transaction, send_with_confirmation, split_member_spec, Confirmation,
+    Member, SendProgress, TransactionConfig};
+use crate::config::{UrlArg, KeypairArg, CommitmentArg, TransactionArgs, pubkey_or_signer_path, pubkey_arg};
+use instruction_file::read_instructions;
+use output::*;
+
+mod instruction_file;
+mod output;
 
 
 /// Multisig CLI
@@ -20,27 +35,74 @@ pub struct Opts {
     pub url: UrlArg,
     #[clap(flatten)]
     pub keypair: KeypairArg,
+    #[clap(flatten)]
+    pub commitment: CommitmentArg,
+    #[clap(flatten)]
+    pub tx_args: TransactionArgs,
+    /// Format of the command's output.
+    /// The JSON formats are meant for scripts, and print nothing else to stdout.
+    #[clap(long, arg_enum, global = true, default_value = "display")]
+    pub output: OutputFormat,
     #[clap(subcommand)]
     pub command: Command,
 }
 
+/// Options shared by every command that submits a new transaction proposal.
+#[derive(Debug, Parser)]
+pub struct ProposalArgs {
+    /// Seconds until the proposal expires, or 0 for no expiry.
+    /// Defaults to the multisig wallet's default TTL.
+    #[clap(long)]
+    pub ttl: Option<u32>,
+    /// Also approve the proposal in the same transaction.
+    #[clap(long)]
+    pub approve: bool,
+}
+
+impl ProposalArgs {
+    fn output(&self, transaction: Pubkey, signature: Signature) -> CliProposal {
+        CliProposal {
+            transaction: transaction.to_string(),
+            signature: signature.to_string(),
+            approved: self.approve,
+        }
+    }
+}
+
 
 #[derive(Debug, Parser)]
 pub enum Command {
-    /// Create a new multisig wallet
+    /// Create a new multisig wallet. Doesn't support offline signing or durable nonces.
     NewMultisig {
-        /// This flag adds the configured `-k/--keypair` signer
+        /// This flag adds the configured `-k/--keypair` signer, with a weight of one
         #[clap(long)]
         include_signer: bool,
-        /// Minimum required number of signers to execute a transaction
-        /// signed by the wallet. Must be greater than zero,
-        /// no greater than the total number of members.
+        /// Minimum required combined weight of the approving members to execute
+        /// a transaction signed by the wallet. Must be greater than zero,
+        /// no greater than the total weight of the members.
         #[clap(long)]
         threshold: u16,
         /// List of members. Must be unique. Can be either a base-58 pubkey string,
-        /// or any path compatible with the `-k/--keypair` flag.
+        /// or any path compatible with the `-k/--keypair` flag, optionally followed
+        /// by `:WEIGHT` to give the member's vote that weight (one by default).
         members: Vec<String>,
     },
+    /// Propose a transaction made of arbitrary instructions, read from a
+    /// JSON or YAML file. Each instruction lists its program id, its accounts
+    /// with signer/writable flags, and its data encoded as base58, base64 or hex.
+    Propose {
+        /// The target multisig wallet on which to propose a new transaction.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Pubkey,
+        /// Path to the file containing the list of instructions.
+        #[clap(long)]
+        from_file: PathBuf,
+        /// The vault that signs the instructions upon execution.
+        #[clap(long, default_value = "0")]
+        vault_index: u8,
+        #[clap(flatten)]
+        proposal: ProposalArgs,
+    },
     /// Demonstrate the create transaction functionality with a simple
     /// memo transaction.
     ProposeMemo {
@@ -50,18 +112,44 @@ pub enum Command {
         /// The memo message to propose (the multisig address will sign a memo
         /// instruction with this memo string).
         memo: String,
+        /// Seconds until the proposal expires, or 0 for no expiry.
+        /// Defaults to the multisig wallet's default TTL.
+        #[clap(long)]
+        ttl: Option<u32>,
     },
     /// Approve a transaction
     Approve {
         /// Target transaction to approve.
         #[clap(parse(try_from_str=pubkey_arg))]
         transaction: Pubkey,
+        /// The multisig wallet of the transaction. Otherwise it is read from
+        /// the transaction account, so this is required to sign offline.
+        #[clap(long, parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Option<Pubkey>,
+        /// Print the proposed instructions and ask for confirmation before signing.
+        #[clap(long)]
+        review: bool,
     },
     /// Cancel approval of a transaction
     Unapprove {
         /// Target transaction to unapprove.
         #[clap(parse(try_from_str=pubkey_arg))]
         transaction: Pubkey,
+        /// The multisig wallet of the transaction. Otherwise it is read from
+        /// the transaction account, so this is required to sign offline.
+        #[clap(long, parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Option<Pubkey>,
+    },
+    /// Reject a transaction. Once enough members reject it that the threshold
+    /// can no longer be reached, the transaction is cancelled.
+    Reject {
+        /// Target transaction to reject.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        transaction: Pubkey,
+        /// The multisig wallet of the transaction. Otherwise it is read from
+        /// the transaction account, so this is required to sign offline.
+        #[clap(long, parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Option<Pubkey>,
     },
     /// Execute a transaction. Requires that the threshold of approvals is reached.
     Execute {
@@ -69,15 +157,46 @@ pub enum Command {
         #[clap(parse(try_from_str=pubkey_arg))]
         transaction: Pubkey,
     },
+    /// Simulate executing a transaction, reporting its logs and balance changes.
+    /// Signatures aren't verified, so this works before the threshold is reached.
+    Simulate {
+        /// Target transaction to simulate.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        transaction: Pubkey,
+    },
     /// Propose a new threshold on the given multisig.
     ProposeNewThreshold {
         /// The target multisig wallet on which to propose a new threshold.
         #[clap(parse(try_from_str=pubkey_arg))]
         multisig_wallet: Pubkey,
-        /// Minimum required number of signers to execute a transaction
-        /// signed by the wallet. Must be greater than zero,
-        /// no greater than the total number of members.
+        /// Minimum required combined weight of the approving members to execute
+        /// a transaction signed by the wallet. Must be greater than zero,
+        /// no greater than the total weight of the members.
         threshold: u16,
+        #[clap(flatten)]
+        proposal: ProposalArgs,
+    },
+    /// Propose a new timelock on the given multisig.
+    ProposeNewMinDelay {
+        /// The target multisig wallet on which to propose a new timelock.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Pubkey,
+        /// Minimum number of seconds between a transaction reaching the threshold
+        /// of approvals, and its execution.
+        min_delay: u32,
+        #[clap(flatten)]
+        proposal: ProposalArgs,
+    },
+    /// Propose a new default time-to-live for transactions on the given multisig.
+    ProposeNewDefaultTtl {
+        /// The target multisig wallet on which to propose a new default TTL.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Pubkey,
+        /// Seconds until a new transaction expires, unless otherwise specified
+        /// by its proposer. Zero means that transactions don't expire by default.
+        default_ttl: u32,
+        #[clap(flatten)]
+        proposal: ProposalArgs,
     },
     /// Propose a new set of members on the given multisig.
     ProposeNewMembers {
@@ -85,91 +204,257 @@ pub enum Command {
         #[clap(parse(try_from_str=pubkey_arg))]
         multisig_wallet: Pubkey,
         /// List of members. Must be unique. Can be either a base-58 pubkey string,
-        /// or any path compatible with the `-k/--keypair` flag.
+        /// or any path compatible with the `-k/--keypair` flag, optionally followed
+        /// by `:WEIGHT` to give the member's vote that weight (one by default).
         members: Vec<String>,
+        #[clap(flatten)]
+        proposal: ProposalArgs,
+    },
+    /// Close every transaction on the given multisig that can no longer be executed:
+    /// executed, cancelled or expired ones, and ones that belong to a previous
+    /// wallet configuration.
+    /// Rent is returned to each transaction's proposer.
+    /// Doesn't support offline signing or durable nonces.
+    Close {
+        /// The multisig wallet whose transactions will be closed.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Pubkey,
+    },
+    /// Show the addresses and balances of a multisig wallet's vaults.
+    /// Vaults are the accounts that hold funds on behalf of the multisig wallet.
+    Vault {
+        /// The multisig wallet that owns the vaults.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Pubkey,
+        /// Vault indexes to show. Defaults to only the first vault (index 0).
+        indexes: Vec<u8>,
+    },
+    /// Show a multisig wallet's configuration, and the balances of its vaults.
+    ShowWallet {
+        /// The multisig wallet to show.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Pubkey,
+    },
+    /// Show a transaction proposal: its instructions, who approved or rejected it
+    /// and when, and its current status.
+    ShowTransaction {
+        /// The transaction to show.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        transaction: Pubkey,
+    },
+    /// List every transaction on the given multisig that hasn't been closed,
+    /// along with its status.
+    ListTransactions {
+        /// The multisig wallet whose transactions will be listed.
+        #[clap(parse(try_from_str=pubkey_arg))]
+        multisig_wallet: Pubkey,
     },
 }
 
 pub fn entry(
     opts: &Opts,
+    matches: &ArgMatches,
     signer: Box<dyn Signer>,
     client: RpcClient,
 ) -> Result<()> {
-    match &opts.command {
+    let output = &opts.output;
+    let tx_args = &opts.tx_args;
+    let formatted = match &opts.command {
         Command::NewMultisig {
             include_signer,
             threshold,
             members,
         } => {
-            let app = Opts::into_app();
-            let matches = app.get_matches();
-            new_multisig(
+            output.formatted_string(&new_multisig(
                 *threshold,
                 members,
                 *include_signer,
+                tx_args,
                 client,
                 signer.as_ref(),
-                &matches,
-            )?;
+                matches,
+            )?)?
+        },
+        Command::Propose {
+            multisig_wallet,
+            from_file,
+            vault_index,
+            proposal,
+        } => {
+            output.formatted_string(&propose(
+                multisig_wallet,
+                from_file,
+                *vault_index,
+                proposal,
+                tx_args,
+                matches,
+                client,
+                signer,
+            )?)?
         },
         Command::ProposeMemo {
             multisig_wallet,
             memo,
+            ttl,
         } => {
-            propose_memo(
+            output.formatted_string(&propose_memo(
                 multisig_wallet,
                 memo,
+                *ttl,
+                tx_args,
+                matches,
+                client,
+                signer,
+            )?)?
+        },
+        Command::Approve { transaction, multisig_wallet, review } => {
+            output.formatted_string(&approve(
+                transaction,
+                *multisig_wallet,
+                *review,
+                tx_args,
+                matches,
                 client,
                 signer,
-            )?;
+            )?)?
         },
-        Command::Approve { transaction} => {
-            approve(
+        Command::Unapprove { transaction, multisig_wallet } => {
+            output.formatted_string(&unapprove(
                 transaction,
+                *multisig_wallet,
+                tx_args,
+                matches,
                 client,
                 signer,
-            )?;
+            )?)?
         },
-        Command::Unapprove { transaction} => {
-            unapprove(
+        Command::Reject { transaction, multisig_wallet } => {
+            output.formatted_string(&reject(
                 transaction,
+                *multisig_wallet,
+                tx_args,
+                matches,
                 client,
                 signer,
-            )?;
+            )?)?
         },
         Command::Execute { transaction} => {
-            execute(
+            output.formatted_string(&execute(
                 transaction,
+                tx_args,
+                matches,
                 client,
                 signer,
-            )?;
+            )?)?
+        },
+        Command::Simulate { transaction } => {
+            output.formatted_string(&simulate(
+                transaction,
+                tx_args,
+                matches,
+                client,
+                signer,
+            )?)?
         },
         Command::ProposeNewThreshold {
             multisig_wallet,
             threshold,
+            proposal,
         } => {
-            propose_new_threshold(
+            output.formatted_string(&propose_new_threshold(
                 multisig_wallet,
                 *threshold,
+                proposal,
+                tx_args,
+                matches,
                 client,
                 signer,
-            )?;
+            )?)?
+        },
+        Command::ProposeNewMinDelay {
+            multisig_wallet,
+            min_delay,
+            proposal,
+        } => {
+            output.formatted_string(&propose_new_min_delay(
+                multisig_wallet,
+                *min_delay,
+                proposal,
+                tx_args,
+                matches,
+                client,
+                signer,
+            )?)?
+        },
+        Command::ProposeNewDefaultTtl {
+            multisig_wallet,
+            default_ttl,
+            proposal,
+        } => {
+            output.formatted_string(&propose_new_default_ttl(
+                multisig_wallet,
+                *default_ttl,
+                proposal,
+                tx_args,
+                matches,
+                client,
+                signer,
+            )?)?
         },
         Command::ProposeNewMembers {
             multisig_wallet,
             members,
+            proposal,
         } => {
-            let app = Opts::into_app();
-            let matches = app.get_matches();
-            propose_new_members(
+            output.formatted_string(&propose_new_members(
                 multisig_wallet,
                 members.clone(),
-                &matches,
+                proposal,
+                tx_args,
+                matches,
                 client,
                 signer,
-            )?;
+            )?)?
         },
-    }
+        Command::Close { multisig_wallet } => {
+            output.formatted_string(&close(
+                multisig_wallet,
+                tx_args,
+                matches,
+                client,
+                signer,
+            )?)?
+        },
+        Command::Vault {
+            multisig_wallet,
+            indexes,
+        } => {
+            output.formatted_string(&vault(
+                multisig_wallet,
+                indexes,
+                client,
+            )?)?
+        },
+        Command::ShowWallet { multisig_wallet } => {
+            output.formatted_string(&show_wallet(
+                multisig_wallet,
+                client,
+            )?)?
+        },
+        Command::ShowTransaction { transaction } => {
+            output.formatted_string(&show_transaction(
+                transaction,
+                client,
+            )?)?
+        },
+        Command::ListTransactions { multisig_wallet } => {
+            output.formatted_string(&list_transactions(
+                multisig_wallet,
+                client,
+            )?)?
+        },
+    };
+    println!("{}", formatted);
     Ok(())
 }
 
@@ -177,39 +462,63 @@ pub fn new_multisig(
     threshold: u16,
     members: &Vec<String>,
     include_signer: bool,
+    tx_args: &TransactionArgs,
     client: RpcClient,
     payer: &dyn Signer,
     matches: &ArgMatches,
-) -> Result<()> {
-    let mut members: Vec<Pubkey> = members
-        .iter()
-        .map(|path| pubkey_or_signer_path(path, matches))
-        .flatten()
-        .collect();
+) -> Result<CliNewMultisig> {
+    // The wallet is derived from a throwaway base keypair that signs it into existence.
+    tx_args.ensure_online("new-multisig")?;
+    let mut members = resolve_members(members, matches)?;
     if include_signer {
-        members.push(payer.pubkey());
+        members.push(Member { key: payer.pubkey(), weight: 1 });
     }
     let base = Keypair::new();
     let multisig_address = find_multisig_wallet_address(&base.pubkey());
-    println!("Creating multisig wallet: {}", multisig_address.to_string());
-    let signature = new_multisig_rpc(
-        threshold,
-        members.clone(),
-        &client,
-        payer,
-        Some(&base),
+    let ix = new_multisig_ix(threshold, members, &base.pubkey(), &payer.pubkey());
+    let fee_payer = tx_args.resolve_fee_payer(matches)?;
+    let config = TransactionConfig {
+        signers: vec![payer, &base],
+        ..Default::default()
+    };
+    let signature = send_with_spinner(&client, &|| {
+        build_transaction(&client, std::slice::from_ref(&ix), fee_payer.as_deref().unwrap_or(payer), &config)
+    })?;
+    Ok(CliNewMultisig {
+        multisig_wallet: multisig_address.to_string(),
+        signature: signature.to_string(),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn propose(
+    multisig_wallet: &Pubkey,
+    from_file: &Path,
+    vault_index: u8,
+    proposal: &ProposalArgs,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+    client: RpcClient,
+    signer: Box<dyn Signer>,
+) -> Result<CliOutcome<CliProposal>> {
+    let instructions = read_instructions(from_file)?;
+    let mut member = MultisigMember::try_new(
+        signer,
+        *multisig_wallet,
+        client,
     )?;
-    println!("New multisig successfully created. \
-    signature: {}", signature.to_string());
-    Ok(())
+    submit_proposal(&mut member, instructions, vault_index, proposal, tx_args, matches)
 }
 
 pub fn propose_memo(
     multisig_wallet: &Pubkey,
     memo: &str,
+    ttl: Option<u32>,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
     client: RpcClient,
     signer: Box<dyn Signer>,
-) -> Result<()> {
+) -> Result<CliOutcome<CliProposal>> {
     let memo_ix = spl_memo::build_memo(
         memo.as_ref(), &[multisig_wallet]);
     let mut member = MultisigMember::try_new(
@@ -217,37 +526,114 @@ pub fn propose_memo(
         multisig_wallet.clone(),
         client,
     )?;
-    let proposal = member.next_transaction_pubkey();
-    println!("Creating transaction proposal: {}", proposal.to_string());
-    let signature = member.create_and_approve_tx(vec![memo_ix])?;
-    println!("New transaction proposal successfully created. \
-    signature: {}", signature.to_string());
-    Ok(())
+    let proposal = ProposalArgs { ttl, approve: true };
+    submit_proposal(&mut member, vec![memo_ix], 0, &proposal, tx_args, matches)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn approve(
     transaction: &Pubkey,
+    multisig_wallet: Option<Pubkey>,
+    review: bool,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
     client: RpcClient,
     signer: Box<dyn Signer>
-) -> Result<()> {
-    let act_data = client.get_account_data(transaction)?;
-    let tx_data = MultisigTransaction::try_deserialize(&mut act_data.as_slice())?;
-    let member = MultisigMember::try_new(
-        signer,
-        tx_data.multisig_wallet.clone(),
-        client,
-    )?;
-    let signature = member.approve_rpc(transaction.clone())?;
-    println!("Transaction successfully approved. \
-    signature: {}", signature.to_string());
-    Ok(())
+) -> Result<CliOutcome<CliApproval>> {
+    // Needed to review the transaction, or to report when it becomes executable.
+    let tx_data = if tx_args.sign_only && multisig_wallet.is_some() && !review {
+        None
+    } else {
+        Some(fetch_transaction(transaction, &client)?)
+    };
+    if let Some(tx_data) = tx_data.as_ref().filter(|_| review) {
+        confirm_instructions(&tx_data.instructions)?;
+    }
+    let multisig_wallet = approval_wallet(multisig_wallet, tx_data.as_ref())?;
+    let ix = approval_ix(
+        multisig_demo::instruction::Approve.data(),
+        multisig_wallet,
+        *transaction,
+        signer.pubkey(),
+    );
+    let signature = match submit(&client, signer.as_ref(), &[ix], tx_args, matches)? {
+        CliOutcome::Sent(signature) => signature,
+        CliOutcome::SignOnly(sign_only) => return Ok(CliOutcome::SignOnly(sign_only)),
+    };
+    let mut approval = CliApproval {
+        signature: signature.to_string(),
+        executable_at: None,
+        executable_in: None,
+    };
+    if let Some(tx_data) = tx_data {
+        let wallet = fetch_wallet(&multisig_wallet, &client)?;
+        approval.executable_at = tx_data.executable_at(&wallet);
+        let weight = wallet.member_index(&signer.pubkey())
+            .map_or(0, |index| wallet.members[index].weight as u64);
+        approval.executable_in = (approval.executable_at.is_none() &&
+            tx_data.approved_weight(&wallet) + weight >= wallet.threshold as u64)
+            .then_some(wallet.min_delay);
+    }
+    Ok(CliOutcome::Sent(approval))
 }
 
 pub fn unapprove(
     transaction: &Pubkey,
+    multisig_wallet: Option<Pubkey>,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
     client: RpcClient,
     signer: Box<dyn Signer>
-) -> Result<()> {
+) -> Result<CliOutcome<CliSignature>> {
+    let tx_data = match multisig_wallet {
+        Some(_) => None,
+        None => Some(fetch_transaction(transaction, &client)?),
+    };
+    let ix = approval_ix(
+        multisig_demo::instruction::Unapprove.data(),
+        approval_wallet(multisig_wallet, tx_data.as_ref())?,
+        *transaction,
+        signer.pubkey(),
+    );
+    Ok(submit(&client, signer.as_ref(), &[ix], tx_args, matches)?
+        .map(|signature| CliSignature {
+            message: "Transaction approval succesfully cancelled.",
+            signature: signature.to_string(),
+        }))
+}
+
+pub fn reject(
+    transaction: &Pubkey,
+    multisig_wallet: Option<Pubkey>,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+    client: RpcClient,
+    signer: Box<dyn Signer>
+) -> Result<CliOutcome<CliSignature>> {
+    let tx_data = match multisig_wallet {
+        Some(_) => None,
+        None => Some(fetch_transaction(transaction, &client)?),
+    };
+    let ix = approval_ix(
+        multisig_demo::instruction::Reject.data(),
+        approval_wallet(multisig_wallet, tx_data.as_ref())?,
+        *transaction,
+        signer.pubkey(),
+    );
+    Ok(submit(&client, signer.as_ref(), &[ix], tx_args, matches)?
+        .map(|signature| CliSignature {
+            message: "Transaction successfully rejected.",
+            signature: signature.to_string(),
+        }))
+}
+
+pub fn execute(
+    transaction: &Pubkey,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+    client: RpcClient,
+    signer: Box<dyn Signer>
+) -> Result<CliOutcome<CliExecution>> {
     let act_data = client.get_account_data(transaction)?;
     let tx_data = MultisigTransaction::try_deserialize(&mut act_data.as_slice())?;
     let member = MultisigMember::try_new(
@@ -255,73 +641,432 @@ pub fn unapprove(
         tx_data.multisig_wallet.clone(),
         client,
     )?;
-    let signature = member.unapprove_rpc(transaction.clone())?;
-    println!("Transaction approval succesfully cancelled. \
-    signature: {}", signature.to_string());
-    Ok(())
+    let ix = member.execute_ix(*transaction, tx_data);
+    Ok(submit(member.client(), member.signer(), &[ix], tx_args, matches)?
+        .map(|signature| CliExecution {
+            signature: signature.to_string(),
+        }))
 }
 
-pub fn execute(
+pub fn simulate(
     transaction: &Pubkey,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
     client: RpcClient,
     signer: Box<dyn Signer>
-) -> Result<()> {
-    let act_data = client.get_account_data(transaction)?;
-    let tx_data = MultisigTransaction::try_deserialize(&mut act_data.as_slice())?;
-    let member = MultisigMember::try_new(
+) -> Result<CliSimulation> {
+    let tx_data = fetch_transaction(transaction, &client)?;
+    let mut member = MultisigMember::try_new(
         signer,
-        tx_data.multisig_wallet.clone(),
+        tx_data.multisig_wallet,
         client,
     )?;
-    let signature = member.execute_rpc(transaction.clone())?;
-    println!("Transaction succesfully executed. \
-    signature: {}", signature.to_string());
-    // Hard-coded localnet explorer URL, because this is a demo!
-    println!("https://explorer.solana.com/tx/{}?cluster=custom&customUrl=http%3A%2F%2Flocalhost%3A8899",
-        signature.to_string());
-    Ok(())
+    if let Some(fee_payer) = tx_args.resolve_fee_payer(matches)? {
+        member = member.with_fee_payer(fee_payer);
+    }
+    let simulation = member.simulate_execute(*transaction)?;
+    Ok(CliSimulation {
+        transaction: transaction.to_string(),
+        via_execute: simulation.via_execute,
+        error: simulation.err.map(|err| err.to_string()),
+        units_consumed: simulation.units_consumed,
+        logs: simulation.logs,
+        balance_changes: simulation.balance_changes
+            .iter()
+            .map(|change| CliBalanceChange {
+                address: change.address.to_string(),
+                pre_lamports: change.pre_lamports,
+                post_lamports: change.post_lamports,
+                token: change.token_delta().map(|(mint, delta)| CliTokenDelta {
+                    mint: mint.to_string(),
+                    pre_amount: change.pre_token.map_or(0, |balance| balance.amount),
+                    post_amount: change.post_token.map_or(0, |balance| balance.amount),
+                    delta: format!("{:+}", delta),
+                }),
+                lamports_delta: format!("{:+}", change.lamports_delta()),
+            })
+            .collect(),
+    })
 }
 
 pub fn propose_new_threshold(
     multisig_wallet: &Pubkey,
     threshold: u16,
+    proposal: &ProposalArgs,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
     client: RpcClient,
     signer: Box<dyn Signer>,
-) -> Result<()> {
-    let member = MultisigMember::try_new(
+) -> Result<CliOutcome<CliProposal>> {
+    let mut member = MultisigMember::try_new(
         signer,
         multisig_wallet.clone(),
         client,
     )?;
-    println!("New threshold: {}", threshold);
-    println!("Creating transaction proposal: {}", member.next_transaction_pubkey().to_string());
-    let signature = member.propose_change_threshold(threshold)?;
-    println!("New transaction proposal successfully created. \
-    signature: {}", signature.to_string());
-    Ok(())
+    let ix = member.change_threshold_ix(threshold);
+    submit_proposal(&mut member, vec![ix], 0, proposal, tx_args, matches)
+}
+
+pub fn propose_new_min_delay(
+    multisig_wallet: &Pubkey,
+    min_delay: u32,
+    proposal: &ProposalArgs,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+    client: RpcClient,
+    signer: Box<dyn Signer>,
+) -> Result<CliOutcome<CliProposal>> {
+    let mut member = MultisigMember::try_new(
+        signer,
+        *multisig_wallet,
+        client,
+    )?;
+    let ix = member.change_min_delay_ix(min_delay);
+    submit_proposal(&mut member, vec![ix], 0, proposal, tx_args, matches)
+}
+
+pub fn propose_new_default_ttl(
+    multisig_wallet: &Pubkey,
+    default_ttl: u32,
+    proposal: &ProposalArgs,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+    client: RpcClient,
+    signer: Box<dyn Signer>,
+) -> Result<CliOutcome<CliProposal>> {
+    let mut member = MultisigMember::try_new(
+        signer,
+        *multisig_wallet,
+        client,
+    )?;
+    let ix = member.change_default_ttl_ix(default_ttl);
+    submit_proposal(&mut member, vec![ix], 0, proposal, tx_args, matches)
 }
 
 pub fn propose_new_members(
     multisig_wallet: &Pubkey,
     members: Vec<String>,
+    proposal: &ProposalArgs,
+    tx_args: &TransactionArgs,
     matches: &ArgMatches,
     client: RpcClient,
     signer: Box<dyn Signer>,
-) -> Result<()> {
-    let members: Vec<Pubkey> = members
-        .iter()
-        .map(|path| pubkey_or_signer_path(path, matches))
-        .flatten()
-        .collect();
-    let member = MultisigMember::try_new(
+) -> Result<CliOutcome<CliProposal>> {
+    let members = resolve_members(&members, matches)?;
+    let mut member = MultisigMember::try_new(
         signer,
         multisig_wallet.clone(),
         client,
     )?;
-    println!("New members: {:?}", members);
-    println!("Creating transaction proposal: {}", member.next_transaction_pubkey().to_string());
-    let signature = member.propose_change_members(members)?;
-    println!("New transaction proposal successfully created. \
-    signature: {}", signature.to_string());
-    Ok(())
+    let ix = member.change_members_ix(members);
+    submit_proposal(&mut member, vec![ix], 0, proposal, tx_args, matches)
+}
+
+pub fn close(
+    multisig_wallet: &Pubkey,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+    client: RpcClient,
+    signer: Box<dyn Signer>,
+) -> Result<CliClose> {
+    // Each closeable transaction is closed in a transaction of its own.
+    tx_args.ensure_online("close")?;
+    let mut member = MultisigMember::try_new(
+        signer,
+        *multisig_wallet,
+        client,
+    )?;
+    if let Some(fee_payer) = tx_args.resolve_fee_payer(matches)? {
+        member = member.with_fee_payer(fee_payer);
+    }
+    let wallet = member.wallet();
+    let now = unix_timestamp()?;
+    let closeable: Vec<(Pubkey, MultisigTransaction)> = member.fetch_transactions()?
+        .into_iter()
+        .filter(|(_, tx)| tx.is_closeable(wallet, now))
+        .collect();
+    // Expiry is judged by the cluster's clock, so a transaction that just expired
+    // locally may not be closeable yet. That shouldn't stop the others from closing.
+    let mut closed = vec![];
+    let mut failed = vec![];
+    for (transaction, data) in closeable {
+        let ixs = [member.close_transaction_ix(transaction, data.proposer)];
+        let build = || member.build_transaction(&ixs, &TransactionConfig::default());
+        match send_with_spinner(member.client(), &build) {
+            Ok(signature) => closed.push(CliClosedTransaction {
+                transaction: transaction.to_string(),
+                proposer: data.proposer.to_string(),
+                signature: signature.to_string(),
+            }),
+            Err(err) => failed.push(CliCloseFailure {
+                transaction: transaction.to_string(),
+                error: err.to_string(),
+            }),
+        }
+    }
+    Ok(CliClose { closed, failed })
+}
+
+pub fn vault(
+    multisig_wallet: &Pubkey,
+    indexes: &[u8],
+    client: RpcClient,
+) -> Result<CliVaults> {
+    let indexes = if indexes.is_empty() {
+        vec![0]
+    } else {
+        indexes.to_vec()
+    };
+    Ok(CliVaults {
+        vaults: fetch_vaults(multisig_wallet, indexes, &client)?,
+    })
+}
+
+pub fn show_wallet(
+    multisig_wallet: &Pubkey,
+    client: RpcClient,
+) -> Result<CliWallet> {
+    let wallet = fetch_wallet(multisig_wallet, &client)?;
+    // Show the first vault, and any other vault that an open transaction uses.
+    let mut indexes: Vec<u8> = fetch_wallet_transactions(multisig_wallet, wallet.tx_nonce, &client)?
+        .iter()
+        .map(|(_, tx)| tx.vault_index)
+        .collect();
+    indexes.push(0);
+    indexes.sort_unstable();
+    indexes.dedup();
+    Ok(CliWallet {
+        address: multisig_wallet.to_string(),
+        threshold: wallet.threshold,
+        total_weight: MultisigWallet::total_weight(&wallet.members),
+        members: wallet.members.iter().map(CliMember::from).collect(),
+        min_delay: wallet.min_delay,
+        default_ttl: wallet.default_ttl,
+        tx_nonce: wallet.tx_nonce,
+        config_seqno: wallet.config_seqno,
+        vaults: fetch_vaults(multisig_wallet, indexes, &client)?,
+    })
+}
+
+pub fn show_transaction(
+    transaction: &Pubkey,
+    client: RpcClient,
+) -> Result<CliTransaction> {
+    let tx = fetch_transaction(transaction, &client)?;
+    let wallet = fetch_wallet(&tx.multisig_wallet, &client)?;
+    let instructions = cli_instructions(&tx.instructions);
+    let votes = (tx.config_seqno == wallet.config_seqno).then(|| {
+        wallet.members
+            .iter()
+            .enumerate()
+            .map(|(i, member)| CliVote {
+                member: member.key.to_string(),
+                weight: member.weight,
+                approved_at: tx.approved.get(i).copied().flatten(),
+                rejected_at: tx.rejected.get(i).copied().flatten(),
+            })
+            .collect()
+    });
+    Ok(CliTransaction {
+        address: transaction.to_string(),
+        multisig_wallet: tx.multisig_wallet.to_string(),
+        status: TransactionStatus::of(&tx, &wallet, unix_timestamp()?),
+        proposer: tx.proposer.to_string(),
+        created_at: tx.created_at,
+        expires_at: tx.expires_at,
+        executor: tx.executor.map(|executor| executor.to_string()),
+        vault_index: tx.vault_index,
+        vault: find_vault_address(&tx.multisig_wallet, tx.vault_index).to_string(),
+        instructions,
+        votes,
+    })
+}
+
+pub fn list_transactions(
+    multisig_wallet: &Pubkey,
+    client: RpcClient,
+) -> Result<CliTransactionList> {
+    let wallet = fetch_wallet(multisig_wallet, &client)?;
+    let now = unix_timestamp()?;
+    let transactions = fetch_wallet_transactions(multisig_wallet, wallet.tx_nonce, &client)?
+        .into_iter()
+        .map(|(address, tx)| CliTransactionSummary {
+            address: address.to_string(),
+            num_instructions: tx.instructions.len(),
+            status: TransactionStatus::of(&tx, &wallet, now),
+        })
+        .collect();
+    Ok(CliTransactionList { transactions })
+}
+
+fn fetch_vaults(
+    multisig_wallet: &Pubkey,
+    indexes: Vec<u8>,
+    client: &RpcClient,
+) -> Result<Vec<CliVault>> {
+    indexes
+        .into_iter()
+        .map(|index| {
+            let vault = find_vault_address(multisig_wallet, index);
+            Ok(CliVault {
+                index,
+                address: vault.to_string(),
+                balance: client.get_balance(&vault)?,
+            })
+        })
+        .collect()
+}
+
+/// Sign `instructions` with `signer` and send them, or with `--sign-only`,
+/// return the signatures for another machine to send.
+/// Fees are paid by `--fee-payer`, which defaults to `signer`.
+fn submit(
+    client: &RpcClient,
+    signer: &dyn Signer,
+    instructions: &[Instruction],
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+) -> Result<CliOutcome<Signature>> {
+    let fee_payer = tx_args.resolve_fee_payer(matches)?;
+    let nonce_authority = tx_args.resolve_nonce_authority(matches)?;
+    let presigners: Vec<Presigner> = tx_args.signers
+        .iter()
+        .map(|(pubkey, signature)| Presigner::new(pubkey, signature))
+        .collect();
+    let config = TransactionConfig {
+        blockhash: tx_args.blockhash,
+        nonce: tx_args.nonce
+            .map(|nonce| (nonce, nonce_authority.as_deref().unwrap_or(signer))),
+        signers: std::iter::once(signer)
+            .chain(presigners.iter().map(|presigner| presigner as &dyn Signer))
+            .collect(),
+    };
+    let payer = fee_payer.as_deref().unwrap_or(signer);
+    let tx = build_transaction(client, instructions, payer, &config)?;
+    let sign_only = CliSignOnly::new(&tx, tx_args.dump_transaction_message);
+    if tx_args.sign_only {
+        return Ok(CliOutcome::SignOnly(sign_only));
+    }
+    if !sign_only.absent.is_empty() || !sign_only.bad_sig.is_empty() {
+        return Err(anyhow!("transaction is missing valid signatures from: {:?}",
+            [sign_only.absent, sign_only.bad_sig].concat()));
+    }
+    // Send the transaction that was just signed, and only sign again over a newer
+    // blockhash if it expires, so that signers such as hardware wallets are asked once.
+    let signed = Cell::new(Some(tx));
+    let build = || match signed.take() {
+        Some(tx) => Ok(tx),
+        None => build_transaction(client, instructions, payer, &config),
+    };
+    Ok(CliOutcome::Sent(send_with_spinner(client, &build)?))
+}
+
+/// Send the transaction made by `build`, and wait until it reaches the client's
+/// commitment level, showing progress on stderr.
+fn send_with_spinner(
+    client: &RpcClient,
+    build: &dyn Fn() -> Result<Transaction>,
+) -> Result<Signature> {
+    let commitment = client.commitment().commitment;
+    let confirmation = Confirmation {
+        commitment: Some(commitment),
+        ..Default::default()
+    };
+    let spinner = ProgressBar::new_spinner();
+    spinner.enable_steady_tick(100);
+    let result = send_with_confirmation(client, build, &confirmation, &|progress| match progress {
+        SendProgress::Sent { signature, attempt: 0 } => {
+            spinner.set_message(format!("Sent transaction {}", signature));
+        }
+        SendProgress::Sent { signature, attempt } => {
+            spinner.set_message(format!("Blockhash expired, resent as {} (attempt {})",
+                signature, attempt + 1));
+        }
+        SendProgress::Waiting { signature, elapsed } => {
+            spinner.set_message(format!("Waiting for {} to be {} ({}s)",
+                signature, commitment, elapsed.as_secs()));
+        }
+        SendProgress::Confirmed { .. } => {}
+    });
+    spinner.finish_and_clear();
+    result
+}
+
+/// Submit a new proposal, see [submit].
+fn submit_proposal(
+    member: &mut MultisigMember,
+    instructions: Vec<Instruction>,
+    vault_index: u8,
+    proposal: &ProposalArgs,
+    tx_args: &TransactionArgs,
+    matches: &ArgMatches,
+) -> Result<CliOutcome<CliProposal>> {
+    let (address, ixs) = member.propose_ixs(
+        instructions, vault_index, proposal.ttl, proposal.approve);
+    Ok(submit(member.client(), member.signer(), &ixs, tx_args, matches)?
+        .map(|signature| proposal.output(address, signature)))
+}
+
+/// The multisig wallet targeted by an [multisig_demo::accounts::Approval] instruction,
+/// either as given on the command-line, or read from the transaction.
+fn approval_wallet(
+    multisig_wallet: Option<Pubkey>,
+    tx_data: Option<&MultisigTransaction>,
+) -> Result<Pubkey> {
+    multisig_wallet
+        .or_else(|| tx_data.map(|tx| tx.multisig_wallet))
+        .ok_or_else(|| anyhow!("the multisig wallet is unknown"))
+}
+
+/// Resolve `MEMBER[:WEIGHT]` specs, where each member is a pubkey or a signer path.
+fn resolve_members(specs: &[String], matches: &ArgMatches) -> Result<Vec<Member>> {
+    specs.iter()
+        .map(|spec| {
+            let (path, weight) = split_member_spec(spec)?;
+            Ok(Member { key: pubkey_or_signer_path(path, matches)?, weight })
+        })
+        .collect()
+}
+
+/// Expiry and timelocks are ultimately judged by the cluster's clock, not the local one,
+/// so times close to a deadline may be off by a few seconds.
+fn unix_timestamp() -> Result<i64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64)
+}
+
+fn cli_instructions(instructions: &[multisig_demo::state::Instruction]) -> Vec<CliInstruction> {
+    let registry = DecoderRegistry::default();
+    instructions
+        .iter()
+        .map(|ix| CliInstruction {
+            program_id: ix.program_id.to_string(),
+            accounts: ix.keys
+                .iter()
+                .map(|meta| CliAccountMeta {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: hex::encode(&ix.data),
+            decoded: registry.decode(&ix.clone().into()),
+        })
+        .collect()
+}
+
+/// Print the decoded `instructions` and wait for the user to confirm them.
+/// This goes through stderr, so that stdout stays parseable with `--output json`.
+fn confirm_instructions(instructions: &[multisig_demo::state::Instruction]) -> Result<()> {
+    eprintln!("Instructions:");
+    for (i, ix) in cli_instructions(instructions).iter().enumerate() {
+        eprintln!("  #{} {}", i, ix);
+    }
+    eprint!("Approve? [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    match answer.trim() {
+        "y" | "Y" | "yes" => Ok(()),
+        _ => Err(anyhow!("approval aborted")),
+    }
 }
@@ -0,0 +1,497 @@
+use std::fmt;
+use anyhow::Result;
+use clap::ArgEnum;
+use multisig_demo_sdk::{DecodedInstruction, Member, TransactionStatus};
+use serde::Serialize;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+
+/// Mirrors the `--output` flag of the Solana CLI.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Display,
+    Json,
+    JsonCompact,
+}
+
+impl OutputFormat {
+    pub fn formatted_string<T: Serialize + fmt::Display>(&self, item: &T) -> Result<String> {
+        Ok(match self {
+            OutputFormat::Display => item.to_string(),
+            OutputFormat::Json => serde_json::to_string_pretty(item)?,
+            OutputFormat::JsonCompact => serde_json::to_string(item)?,
+        })
+    }
+}
+
+/// Output of commands that submit a transaction, which may only have been signed
+/// if `--sign-only` was passed.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum CliOutcome<T> {
+    Sent(T),
+    SignOnly(CliSignOnly),
+}
+
+impl<T> CliOutcome<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> CliOutcome<U> {
+        match self {
+            CliOutcome::Sent(sent) => CliOutcome::Sent(f(sent)),
+            CliOutcome::SignOnly(sign_only) => CliOutcome::SignOnly(sign_only),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for CliOutcome<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliOutcome::Sent(sent) => sent.fmt(f),
+            CliOutcome::SignOnly(sign_only) => sign_only.fmt(f),
+        }
+    }
+}
+
+/// Signatures collected in `--sign-only` mode, to be passed to another machine
+/// with `--signer PUBKEY=SIGNATURE`. Matches the Solana CLI's sign-only output.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSignOnly {
+    pub blockhash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub signers: Vec<String>,
+    pub absent: Vec<String>,
+    pub bad_sig: Vec<String>,
+}
+
+impl CliSignOnly {
+    pub fn new(tx: &Transaction, dump_transaction_message: bool) -> Self {
+        let message_data = tx.message_data();
+        let mut signers = vec![];
+        let mut absent = vec![];
+        let mut bad_sig = vec![];
+        for (signature, pubkey) in tx.signatures.iter().zip(&tx.message.account_keys) {
+            if *signature == Signature::default() {
+                absent.push(pubkey.to_string());
+            } else if signature.verify(pubkey.as_ref(), &message_data) {
+                signers.push(format!("{}={}", pubkey, signature));
+            } else {
+                bad_sig.push(pubkey.to_string());
+            }
+        }
+        Self {
+            blockhash: tx.message.recent_blockhash.to_string(),
+            message: dump_transaction_message.then(|| base64::encode(&message_data)),
+            signers,
+            absent,
+            bad_sig,
+        }
+    }
+}
+
+impl fmt::Display for CliSignOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Blockhash: {}", self.blockhash)?;
+        if let Some(message) = &self.message {
+            write!(f, "\nTransaction Message: {}", message)?;
+        }
+        if !self.signers.is_empty() {
+            write!(f, "\nSigners (Pubkey=Signature):")?;
+            for signer in &self.signers {
+                write!(f, "\n {}", signer)?;
+            }
+        }
+        if !self.absent.is_empty() {
+            write!(f, "\nAbsent Signers (Pubkey):")?;
+            for pubkey in &self.absent {
+                write!(f, "\n {}", pubkey)?;
+            }
+        }
+        if !self.bad_sig.is_empty() {
+            write!(f, "\nBad Signatures (Pubkey):")?;
+            for pubkey in &self.bad_sig {
+                write!(f, "\n {}", pubkey)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliNewMultisig {
+    pub multisig_wallet: String,
+    pub signature: String,
+}
+
+impl fmt::Display for CliNewMultisig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "New multisig successfully created: {}", self.multisig_wallet)?;
+        write!(f, "signature: {}", self.signature)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliProposal {
+    pub transaction: String,
+    pub signature: String,
+    pub approved: bool,
+}
+
+impl fmt::Display for CliProposal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "New transaction proposal successfully created")?;
+        if self.approved {
+            write!(f, " and approved")?;
+        }
+        writeln!(f, ": {}", self.transaction)?;
+        write!(f, "signature: {}", self.signature)
+    }
+}
+
+/// Output of commands whose only result is a transaction signature.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSignature {
+    #[serde(skip)]
+    pub message: &'static str,
+    pub signature: String,
+}
+
+impl fmt::Display for CliSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} signature: {}", self.message, self.signature)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliApproval {
+    pub signature: String,
+    /// Earliest execution time, if the threshold was already reached before this approval.
+    pub executable_at: Option<i64>,
+    /// Timelock started by this approval, if it reached the threshold.
+    pub executable_in: Option<u32>,
+}
+
+impl fmt::Display for CliApproval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Transaction successfully approved. signature: {}", self.signature)?;
+        if let Some(executable_at) = self.executable_at {
+            write!(f, "\nEarliest execution time (unix timestamp): {}", executable_at)?;
+        } else if let Some(executable_in) = self.executable_in {
+            write!(f, "\nApproval threshold reached, executable in {} seconds", executable_in)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliExecution {
+    pub signature: String,
+}
+
+impl fmt::Display for CliExecution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Transaction succesfully executed. signature: {}", self.signature)?;
+        // Hard-coded localnet explorer URL, because this is a demo!
+        write!(f, "https://explorer.solana.com/tx/{}?cluster=custom&customUrl=http%3A%2F%2Flocalhost%3A8899",
+            self.signature)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSimulation {
+    pub transaction: String,
+    /// Whether the simulation went through the execute instruction, rather than
+    /// the proposed instructions on their own.
+    pub via_execute: bool,
+    pub error: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    pub balance_changes: Vec<CliBalanceChange>,
+}
+
+/// Deltas are strings, because they may not fit in a JSON number.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliBalanceChange {
+    pub address: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub lamports_delta: String,
+    pub token: Option<CliTokenDelta>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTokenDelta {
+    pub mint: String,
+    pub pre_amount: u64,
+    pub post_amount: u64,
+    pub delta: String,
+}
+
+impl fmt::Display for CliSimulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Transaction: {}", self.transaction)?;
+        if !self.via_execute {
+            writeln!(f, "Not executable yet, simulated the proposed instructions on their own")?;
+        }
+        match &self.error {
+            Some(error) => writeln!(f, "Result: failed, {}", error)?,
+            None => writeln!(f, "Result: success")?,
+        }
+        if let Some(units_consumed) = self.units_consumed {
+            writeln!(f, "Compute units: {}", units_consumed)?;
+        }
+        writeln!(f, "Logs:")?;
+        for log in &self.logs {
+            writeln!(f, "  {}", log)?;
+        }
+        write!(f, "Balance changes:")?;
+        for change in &self.balance_changes {
+            write!(f, "\n  {}: {} -> {} lamports ({})", change.address,
+                change.pre_lamports, change.post_lamports, change.lamports_delta)?;
+            if let Some(token) = &change.token {
+                write!(f, ", {} -> {} of mint {} ({})",
+                    token.pre_amount, token.post_amount, token.mint, token.delta)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliClosedTransaction {
+    pub transaction: String,
+    pub proposer: String,
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliCloseFailure {
+    pub transaction: String,
+    pub error: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliClose {
+    pub closed: Vec<CliClosedTransaction>,
+    pub failed: Vec<CliCloseFailure>,
+}
+
+impl fmt::Display for CliClose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Closed {} transaction(s)", self.closed.len())?;
+        for closed in &self.closed {
+            write!(f, "\nClosed transaction {}, rent returned to {}. signature: {}",
+                closed.transaction, closed.proposer, closed.signature)?;
+        }
+        for failure in &self.failed {
+            write!(f, "\nFailed to close transaction {}: {}", failure.transaction, failure.error)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliVault {
+    pub index: u8,
+    pub address: String,
+    pub balance: u64,
+}
+
+impl fmt::Display for CliVault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Vault {}: {} (balance: {} lamports)", self.index, self.address, self.balance)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliVaults {
+    pub vaults: Vec<CliVault>,
+}
+
+impl fmt::Display for CliVaults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.vaults.iter().map(|vault| vault.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliMember {
+    pub key: String,
+    pub weight: u16,
+}
+
+impl From<&Member> for CliMember {
+    fn from(member: &Member) -> Self {
+        CliMember {
+            key: member.key.to_string(),
+            weight: member.weight,
+        }
+    }
+}
+
+impl fmt::Display for CliMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (weight {})", self.key, self.weight)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliWallet {
+    pub address: String,
+    pub threshold: u16,
+    pub total_weight: u64,
+    pub members: Vec<CliMember>,
+    pub min_delay: u32,
+    pub default_ttl: u32,
+    pub tx_nonce: u64,
+    pub config_seqno: u32,
+    pub vaults: Vec<CliVault>,
+}
+
+impl fmt::Display for CliWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Multisig wallet: {}", self.address)?;
+        writeln!(f, "Threshold: {} of {} total weight", self.threshold, self.total_weight)?;
+        writeln!(f, "Members:")?;
+        for member in &self.members {
+            writeln!(f, "  {}", member)?;
+        }
+        writeln!(f, "Min delay: {} seconds", self.min_delay)?;
+        writeln!(f, "Default TTL: {} seconds", self.default_ttl)?;
+        writeln!(f, "Transaction nonce: {}", self.tx_nonce)?;
+        writeln!(f, "Config seqno: {}", self.config_seqno)?;
+        write!(f, "Vaults:")?;
+        for vault in &self.vaults {
+            write!(f, "\n  {}", vault)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliInstruction {
+    pub program_id: String,
+    pub accounts: Vec<CliAccountMeta>,
+    /// Hex-encoded instruction data.
+    pub data: String,
+    pub decoded: DecodedInstruction,
+}
+
+impl fmt::Display for CliInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Raw instructions already list their accounts and data.
+        write!(f, "{}", self.decoded.to_string().replace('\n', "\n    "))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliVote {
+    pub member: String,
+    pub weight: u16,
+    pub approved_at: Option<i64>,
+    pub rejected_at: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransaction {
+    pub address: String,
+    pub multisig_wallet: String,
+    pub status: TransactionStatus,
+    pub proposer: String,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub executor: Option<String>,
+    pub vault_index: u8,
+    pub vault: String,
+    pub instructions: Vec<CliInstruction>,
+    /// [None] if the wallet's configuration changed since the transaction was proposed,
+    /// in which case votes can't be attributed to members anymore.
+    pub votes: Option<Vec<CliVote>>,
+}
+
+impl fmt::Display for CliTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Transaction: {}", self.address)?;
+        writeln!(f, "Multisig wallet: {}", self.multisig_wallet)?;
+        writeln!(f, "Status: {}", self.status)?;
+        writeln!(f, "Proposer: {}", self.proposer)?;
+        writeln!(f, "Created at: {}", self.created_at)?;
+        if let Some(expires_at) = self.expires_at {
+            writeln!(f, "Expires at: {}", expires_at)?;
+        }
+        if let Some(executor) = &self.executor {
+            writeln!(f, "Executor: {}", executor)?;
+        }
+        writeln!(f, "Vault {}: {}", self.vault_index, self.vault)?;
+        writeln!(f, "Instructions:")?;
+        for (i, ix) in self.instructions.iter().enumerate() {
+            writeln!(f, "  #{} {}", i, ix)?;
+        }
+        match &self.votes {
+            None => write!(f, "Votes: unavailable, the wallet's configuration changed since this transaction was proposed"),
+            Some(votes) => {
+                write!(f, "Votes:")?;
+                for vote in votes {
+                    let summary = match (vote.approved_at, vote.rejected_at) {
+                        (Some(at), _) => format!("approved at {}", at),
+                        (_, Some(at)) => format!("rejected at {}", at),
+                        _ => "-".to_string(),
+                    };
+                    write!(f, "\n  {} (weight {}): {}", vote.member, vote.weight, summary)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransactionSummary {
+    pub address: String,
+    pub num_instructions: usize,
+    pub status: TransactionStatus,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransactionList {
+    pub transactions: Vec<CliTransactionSummary>,
+}
+
+impl fmt::Display for CliTransactionList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Found {} transaction(s)", self.transactions.len())?;
+        for tx in &self.transactions {
+            write!(f, "\n{}: {} instruction(s), {}", tx.address, tx.num_instructions, tx.status)?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use clap::{Parser, IntoApp};
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::commitment_config::CommitmentConfig;
+use multisig_demo_sdk::SdkError;
 use crate::config::get_solana_cli_config;
 use crate::interface::{entry, Opts};
 
@@ -18,11 +18,17 @@ fn main() -> anyhow::Result<()> {
     // Signer and URL
     let signer = opts.keypair.resolve(&matches, Some(&config))?;
     let url = opts.url.resolve(Some(&config))?;
-    let client = RpcClient::new_with_commitment(
-        &url,CommitmentConfig::processed());
+    let commitment = opts.commitment.resolve(Some(&config))?;
+    let client = RpcClient::new_with_commitment(&url, commitment);
     entry(
         &opts,
+        &matches,
         signer,
         client
-    )
+    ).inspect_err(|err| {
+        // Print the logs of failed transactions, e.g. of a failed preflight simulation.
+        if let Some(sdk_error) = err.downcast_ref::<SdkError>() {
+            sdk_error.logs().iter().for_each(|log| eprintln!("{}", log));
+        }
+    })
 }
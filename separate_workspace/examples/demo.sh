@@ -7,31 +7,29 @@ set -x
 
 # Execute this from up a level,
 # i.e. $ ./examples/demo.sh
+# Addresses are extracted from the CLI's JSON output, which requires `jq`.
 
 solana config set -ul -k ../tests/accounts/test_user1-keypair.json
 
 echo "First we will make a new multisig group (with test users 1,2,3 as members):"
 
 # Make a new multisig
-cargo run -p msig-cli new-multisig \
+MSIG=$(cargo run -p msig-cli -- --output json new-multisig \
     --include-signer \
     --threshold 2 \
     ../tests/accounts/test_user2-keypair.json \
-    ../tests/accounts/test_user3-keypair.json
-
-echo "Copy-paste the new multisig account, and press ENTER: "
-read MSIG
+    ../tests/accounts/test_user3-keypair.json \
+    | jq -r .multisigWallet)
 
 echo "User 2 will now propose a memo and approve it..."
 
-target/debug/msig-cli \
+TX=$(target/debug/msig-cli \
     -k ../tests/accounts/test_user2-keypair.json \
+    --output json \
     propose-memo \
     $MSIG \
-    "hello from the command-line"
-
-echo "Copy-paste the new transaction proposal account, and press ENTER: "
-read TX
+    "hello from the command-line" \
+    | jq -r .transaction)
 
 echo "User 3 Approving..."
 
@@ -40,9 +38,6 @@ target/debug/msig-cli \
     approve \
     $TX
 
-echo "Sleeping to let the validator catch up..."
-sleep 2
-
 echo "User 1 Executing"
 
 target/debug/msig-cli \
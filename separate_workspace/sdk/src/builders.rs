@@ -0,0 +1,252 @@
+use anchor_client::anchor_lang::Id;
+use anchor_client::anchor_lang::InstructionData;
+use anchor_client::anchor_lang::ToAccountMetas;
+use anchor_client::anchor_lang::prelude::System;
+use solana_sdk::instruction::Instruction;
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use multisig_demo::state::{MultisigTransaction, MultisigWallet};
+use crate::instructions::approval_ix;
+use crate::members::{sort_members, Member};
+use crate::pda::{find_multisig_transaction_address, find_vault_address};
+use crate::status::TransactionStatus;
+
+/// Instruction builders for a member of a multisig wallet.
+/// These only depend on the most recently fetched wallet data, so they are shared
+/// by the blocking [crate::MultisigMember] and [crate::nonblocking::MultisigMember],
+/// which both dereference to this.
+#[derive(Clone, Debug)]
+pub struct MemberInstructions {
+    pub member: Pubkey,
+    pub multisig_address: Pubkey,
+    pub multisig_data: MultisigWallet,
+}
+
+impl MemberInstructions {
+    /// The most recently fetched multisig wallet data.
+    pub fn wallet(&self) -> &MultisigWallet {
+        &self.multisig_data
+    }
+
+    /// Returns the pubkey of the next proposal produced on this multisig wallet,
+    /// which updates every [NewTransaction] instruction with the increment of
+    /// the `tx_nonce`.
+    pub fn next_transaction_pubkey(&self) -> Pubkey {
+        find_multisig_transaction_address(
+            &self.multisig_address, self.multisig_data.tx_nonce)
+    }
+
+    /// Returns the pubkey of the vault at `index`, which holds funds on behalf
+    /// of this multisig wallet.
+    pub fn vault_pubkey(&self, index: u8) -> Pubkey {
+        find_vault_address(&self.multisig_address, index)
+    }
+
+    /// Create an instruction that modifies the multisig wallet itself.
+    /// These require the multisig wallet's signature, so they must be embedded
+    /// in a [MultisigTransaction] to be executed.
+    /// The first vault pays for (or is refunded) any change in the wallet's rent.
+    fn administration_ix(&self, data: Vec<u8>) -> Instruction {
+        let acts = multisig_demo::accounts::Administration {
+            multisig_wallet: self.multisig_address,
+            payer: self.vault_pubkey(0),
+            system_program: System::id(),
+        };
+        Instruction {
+            data,
+            accounts: acts.to_account_metas(None),
+            program_id: multisig_demo::ID,
+        }
+    }
+
+    /// Create the instruction to change the approval threshold.
+    pub fn change_threshold_ix(&self, threshold: u16) -> Instruction {
+        self.administration_ix(
+            multisig_demo::instruction::ChangeThreshold { threshold }.data())
+    }
+
+    /// Create the instruction to change the timelock between a transaction
+    /// reaching the approval threshold and its execution.
+    pub fn change_min_delay_ix(&self, min_delay: u32) -> Instruction {
+        self.administration_ix(
+            multisig_demo::instruction::ChangeMinDelay { min_delay }.data())
+    }
+
+    /// Create the instruction to change the default time-to-live of new transactions.
+    pub fn change_default_ttl_ix(&self, default_ttl: u32) -> Instruction {
+        self.administration_ix(
+            multisig_demo::instruction::ChangeDefaultTtl { default_ttl }.data())
+    }
+
+    /// Create the instruction to change the member set.
+    pub fn change_members_ix(&self, mut members: Vec<Member>) -> Instruction {
+        sort_members(&mut members);
+        self.administration_ix(
+            multisig_demo::instruction::ChangeMembers { members }.data())
+    }
+
+    /// Create the instructions to propose a new [MultisigTransaction],
+    /// and optionally approve it in the same transaction. `ttl` is zero for no expiry,
+    /// or [None] for the wallet's default, see [MemberInstructions::new_transaction_ix].
+    /// Returns the address of the proposal along with the instructions.
+    pub fn propose_ixs(
+        &self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> (Pubkey, Vec<Instruction>) {
+        let proposal = self.next_transaction_pubkey();
+        let mut ixs = vec![self.new_transaction_ix(instructions, vault_index, ttl)];
+        if approve {
+            ixs.push(self.approve_ix(proposal));
+        }
+        (proposal, ixs)
+    }
+
+    /// Create the instruction to create a new [MultisigTransaction].
+    /// The vault at `vault_index` signs alongside the multisig wallet upon execution.
+    /// The transaction expires `ttl` seconds after creation, or never if `ttl` is zero.
+    /// If [None], the wallet's default TTL applies.
+    pub fn new_transaction_ix(
+        &self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+    ) -> Instruction {
+        let ix = multisig_demo::instruction::NewTransaction {
+            instructions: instructions.into_iter().map(|ix| ix.into()).collect(),
+            vault_index,
+            ttl,
+        };
+        let transaction = self.next_transaction_pubkey();
+        let acts = multisig_demo::accounts::NewTransaction {
+            proposer: self.member,
+            multisig_wallet: self.multisig_address,
+            transaction,
+            system_program: System::id(),
+        };
+        Instruction {
+            data: ix.data(),
+            accounts: acts.to_account_metas(None),
+            program_id: multisig_demo::ID,
+        }
+    }
+
+    /// Create the instruction to approve a [MultisigTransaction].
+    pub fn approve_ix(&self, transaction: Pubkey) -> Instruction {
+        approval_ix(
+            multisig_demo::instruction::Approve.data(),
+            self.multisig_address,
+            transaction,
+            self.member,
+        )
+    }
+
+    /// Create the instruction to cancel approval of a [MultisigTransaction].
+    pub fn unapprove_ix(&self, transaction: Pubkey) -> Instruction {
+        approval_ix(
+            multisig_demo::instruction::Unapprove.data(),
+            self.multisig_address,
+            transaction,
+            self.member,
+        )
+    }
+
+    /// Create the instruction to reject a [MultisigTransaction].
+    pub fn reject_ix(&self, transaction: Pubkey) -> Instruction {
+        approval_ix(
+            multisig_demo::instruction::Reject.data(),
+            self.multisig_address,
+            transaction,
+            self.member,
+        )
+    }
+
+    /// Create the instruction to execute a [MultisigTransaction].
+    pub fn execute_ix(&self, transaction: Pubkey, data: MultisigTransaction) -> Instruction {
+        let ix = multisig_demo::instruction::Execute;
+        let acts = multisig_demo::accounts::Approval {
+            member: self.member,
+            transaction,
+            multisig_wallet: self.multisig_address,
+        };
+        let mut act_metas = acts.to_account_metas(None);
+        // The vault is a PDA, its signature is provided by the program during execution.
+        let vault = self.vault_pubkey(data.vault_index);
+        data.instructions
+            .into_iter()
+            .for_each(|ix| {
+                ix.keys
+                    .into_iter()
+                    .for_each(|key| {
+                        if key.pubkey == vault {
+                            act_metas.push(AccountMeta {
+                                pubkey: vault,
+                                is_signer: false,
+                                is_writable: key.is_writable,
+                            });
+                        } else if key.pubkey != self.multisig_address {
+                            act_metas.push(key.into());
+                        }
+                    });
+                act_metas.push(AccountMeta {
+                    pubkey: ix.program_id,
+                    is_signer: false,
+                    is_writable: false
+                });
+            });
+        act_metas.push(AccountMeta {
+            pubkey: self.multisig_address,
+            is_signer: false,
+            is_writable: false
+        });
+        Instruction {
+            data: ix.data(),
+            accounts: act_metas,
+            program_id: multisig_demo::ID,
+        }
+    }
+
+    /// Create the instructions to simulate executing a [MultisigTransaction] at
+    /// unix timestamp `now`, see [crate::MultisigMember::simulate_execute].
+    /// Returns whether they go through [Execute], the instructions, and the
+    /// writable accounts of the proposal.
+    pub fn simulate_execute_ixs(
+        &self,
+        transaction: Pubkey,
+        data: MultisigTransaction,
+        now: i64,
+    ) -> (bool, Vec<Instruction>, Vec<Pubkey>) {
+        let via_execute = TransactionStatus::of(&data, &self.multisig_data, now)
+            == TransactionStatus::Executable;
+        let mut addresses: Vec<Pubkey> = vec![];
+        for meta in data.instructions.iter().flat_map(|ix| &ix.keys) {
+            if meta.is_writable && !addresses.contains(&meta.pubkey) {
+                addresses.push(meta.pubkey);
+            }
+        }
+        let instructions: Vec<Instruction> = if via_execute {
+            vec![self.execute_ix(transaction, data)]
+        } else {
+            data.instructions.into_iter().map(Into::into).collect()
+        };
+        (via_execute, instructions, addresses)
+    }
+
+    /// Create the instruction to close a [MultisigTransaction],
+    /// returning its rent to the `proposer`.
+    pub fn close_transaction_ix(&self, transaction: Pubkey, proposer: Pubkey) -> Instruction {
+        let ix = multisig_demo::instruction::CloseTransaction;
+        let acts = multisig_demo::accounts::CloseTransaction {
+            multisig_wallet: self.multisig_address,
+            transaction,
+            proposer,
+        };
+        Instruction {
+            data: ix.data(),
+            accounts: acts.to_account_metas(None),
+            program_id: multisig_demo::ID,
+        }
+    }
+}
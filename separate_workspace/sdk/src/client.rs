@@ -0,0 +1,89 @@
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_program::hash::Hash;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{self, Transaction};
+use crate::error::SdkError;
+use crate::simulation::simulation_config;
+
+/// The cluster calls that the SDK depends on, so that the blocking
+/// [crate::MultisigMember] and the free functions of this crate can run
+/// against something other than an [RpcClient], e.g. a mock in tests.
+/// See [crate::nonblocking::MultisigClient] for the async counterpart,
+/// which is also implemented for the `solana-program-test` `BanksClient`
+/// with the `banks` feature.
+pub trait MultisigClient {
+    /// Fetch an account, or [None] if it doesn't exist.
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>>;
+
+    /// Fetch several accounts at once, in the order of `addresses`.
+    fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>>;
+
+    fn get_latest_blockhash(&self) -> Result<Hash>;
+
+    /// Whether transactions signed over `blockhash` can still land.
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool>;
+
+    /// Submit a fully signed transaction. Failures are returned as an [SdkError].
+    fn send_transaction(&self, tx: &Transaction) -> Result<Signature>;
+
+    /// The result of a sent transaction once it reaches `commitment`, or [None]
+    /// if it hasn't yet.
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<transaction::Result<()>>>;
+
+    /// Simulate `tx` without verifying signatures, over the latest blockhash,
+    /// returning the state of `addresses` afterwards.
+    fn simulate_transaction(
+        &self,
+        tx: &Transaction,
+        addresses: &[Pubkey],
+    ) -> Result<RpcSimulateTransactionResult>;
+}
+
+impl MultisigClient for RpcClient {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.get_account_with_commitment(address, self.commitment())?.value)
+    }
+
+    fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        Ok(RpcClient::get_multiple_accounts(self, addresses)?)
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(RpcClient::get_latest_blockhash(self)?)
+    }
+
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool> {
+        Ok(RpcClient::is_blockhash_valid(self, blockhash, CommitmentConfig::processed())?)
+    }
+
+    fn send_transaction(&self, tx: &Transaction) -> Result<Signature> {
+        Ok(RpcClient::send_transaction(self, tx)
+            .map_err(|err| SdkError::from_client_error(err, tx))
+            ?)
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<transaction::Result<()>>> {
+        Ok(self.get_signature_status_with_commitment(signature, commitment)?)
+    }
+
+    fn simulate_transaction(
+        &self,
+        tx: &Transaction,
+        addresses: &[Pubkey],
+    ) -> Result<RpcSimulateTransactionResult> {
+        Ok(self.simulate_transaction_with_config(tx, simulation_config(addresses))?.value)
+    }
+}
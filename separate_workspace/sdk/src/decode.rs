@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::fmt;
+use anchor_client::anchor_lang::AnchorDeserialize;
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::loader_upgradeable_instruction::UpgradeableLoaderInstruction;
+use solana_sdk::native_token::lamports_to_sol;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use spl_token::instruction::TokenInstruction;
+
+/// Decodes instructions of a single program. Returns `None` when the
+/// instruction isn't recognised, in which case it is rendered as raw data.
+pub type DecodeFn = fn(&Instruction) -> Option<DecodedInstruction>;
+
+/// A human-readable rendering of an [Instruction].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedInstruction {
+    /// Name of the invoked program, or its address if it isn't known.
+    pub program: String,
+    /// Name of the instruction, or `unknown` if it couldn't be decoded.
+    pub name: String,
+    pub fields: Vec<DecodedField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DecodedField {
+    pub name: String,
+    pub value: String,
+}
+
+impl DecodedInstruction {
+    fn new(program: &str, name: &str) -> Self {
+        DecodedInstruction {
+            program: program.to_string(),
+            name: name.to_string(),
+            fields: vec![],
+        }
+    }
+
+    fn field(mut self, name: &str, value: impl ToString) -> Self {
+        self.fields.push(DecodedField {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    /// Add the account at `index`, if the instruction has one there.
+    fn account(self, name: &str, ix: &Instruction, index: usize) -> Self {
+        match ix.accounts.get(index) {
+            Some(meta) => self.field(name, meta.pubkey),
+            None => self,
+        }
+    }
+
+    fn lamports(self, name: &str, lamports: u64) -> Self {
+        self.field(name, format!("{} ({} SOL)", lamports, lamports_to_sol(lamports)))
+    }
+
+    /// Render the accounts and data of an instruction that couldn't be decoded.
+    fn raw(program: &str, ix: &Instruction) -> Self {
+        let mut decoded = DecodedInstruction::new(program, "unknown");
+        for (i, meta) in ix.accounts.iter().enumerate() {
+            let access = match (meta.is_signer, meta.is_writable) {
+                (true, true) => "signer, writable",
+                (true, false) => "signer",
+                (false, true) => "writable",
+                (false, false) => "readonly",
+            };
+            decoded = decoded.field(&format!("account {}", i), format!("{} ({})", meta.pubkey, access));
+        }
+        let data: String = ix.data.iter().map(|b| format!("{:02x}", b)).collect();
+        decoded.field("data", data)
+    }
+}
+
+impl fmt::Display for DecodedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.program, self.name)?;
+        for field in &self.fields {
+            write!(f, "\n  {}: {}", field.name, field.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Registry of instruction decoders, keyed by program id. [DecoderRegistry::default]
+/// knows the System, SPL Token, Memo and BPF Upgradeable Loader programs, as
+/// well as the multisig's own administration instructions.
+pub struct DecoderRegistry {
+    decoders: HashMap<Pubkey, (String, DecodeFn)>,
+}
+
+impl DecoderRegistry {
+    /// A registry without any decoders, which renders everything as raw data.
+    pub fn empty() -> Self {
+        DecoderRegistry {
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Register `decode` for instructions invoking `program_id`, replacing
+    /// any decoder that was registered for it before.
+    pub fn register(&mut self, program_id: Pubkey, program: &str, decode: DecodeFn) {
+        self.decoders.insert(program_id, (program.to_string(), decode));
+    }
+
+    /// Decode `ix`, falling back to its raw accounts and data.
+    pub fn decode(&self, ix: &Instruction) -> DecodedInstruction {
+        match self.decoders.get(&ix.program_id) {
+            Some((program, decode)) => decode(ix)
+                .unwrap_or_else(|| DecodedInstruction::raw(program, ix)),
+            None => DecodedInstruction::raw(&ix.program_id.to_string(), ix),
+        }
+    }
+}
+
+impl Default for DecoderRegistry {
+    fn default() -> Self {
+        let mut registry = DecoderRegistry::empty();
+        registry.register(system_program::ID, SYSTEM, decode_system);
+        registry.register(spl_token::ID, TOKEN, decode_token);
+        registry.register(spl_memo::ID, MEMO, decode_memo);
+        registry.register(spl_memo::v1::ID, MEMO, decode_memo);
+        registry.register(bpf_loader_upgradeable::ID, LOADER, decode_upgradeable_loader);
+        registry.register(multisig_demo::ID, MULTISIG, decode_multisig);
+        registry
+    }
+}
+
+const SYSTEM: &str = "System Program";
+const TOKEN: &str = "SPL Token";
+const MEMO: &str = "Memo";
+const LOADER: &str = "BPF Upgradeable Loader";
+const MULTISIG: &str = "Multisig";
+
+fn decode_system(ix: &Instruction) -> Option<DecodedInstruction> {
+    let decoded = match bincode::deserialize(&ix.data).ok()? {
+        SystemInstruction::Transfer { lamports } => DecodedInstruction::new(SYSTEM, "transfer")
+            .account("from", ix, 0)
+            .account("to", ix, 1)
+            .lamports("lamports", lamports),
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            DecodedInstruction::new(SYSTEM, "create account")
+                .account("funder", ix, 0)
+                .account("new account", ix, 1)
+                .lamports("lamports", lamports)
+                .field("space", space)
+                .field("owner", owner)
+        }
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+fn decode_token(ix: &Instruction) -> Option<DecodedInstruction> {
+    let decoded = match TokenInstruction::unpack(&ix.data).ok()? {
+        TokenInstruction::Transfer { amount } => DecodedInstruction::new(TOKEN, "transfer")
+            .account("source", ix, 0)
+            .account("destination", ix, 1)
+            .account("authority", ix, 2)
+            .field("amount", amount),
+        TokenInstruction::TransferChecked { amount, decimals } => {
+            DecodedInstruction::new(TOKEN, "transfer")
+                .account("source", ix, 0)
+                .account("mint", ix, 1)
+                .account("destination", ix, 2)
+                .account("authority", ix, 3)
+                .field("amount", spl_token::amount_to_ui_amount_string(amount, decimals))
+        }
+        TokenInstruction::Approve { amount } => DecodedInstruction::new(TOKEN, "approve")
+            .account("source", ix, 0)
+            .account("delegate", ix, 1)
+            .account("owner", ix, 2)
+            .field("amount", amount),
+        TokenInstruction::ApproveChecked { amount, decimals } => {
+            DecodedInstruction::new(TOKEN, "approve")
+                .account("source", ix, 0)
+                .account("mint", ix, 1)
+                .account("delegate", ix, 2)
+                .account("owner", ix, 3)
+                .field("amount", spl_token::amount_to_ui_amount_string(amount, decimals))
+        }
+        TokenInstruction::SetAuthority { authority_type, new_authority } => {
+            DecodedInstruction::new(TOKEN, "set authority")
+                .account("account", ix, 0)
+                .account("current authority", ix, 1)
+                .field("authority type", format!("{:?}", authority_type))
+                .field("new authority", Option::<Pubkey>::from(new_authority)
+                    .map_or_else(|| "none".to_string(), |key| key.to_string()))
+        }
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+fn decode_memo(ix: &Instruction) -> Option<DecodedInstruction> {
+    let memo = std::str::from_utf8(&ix.data).ok()?;
+    Some(DecodedInstruction::new(MEMO, "memo").field("memo", memo))
+}
+
+fn decode_upgradeable_loader(ix: &Instruction) -> Option<DecodedInstruction> {
+    let decoded = match bincode::deserialize(&ix.data).ok()? {
+        UpgradeableLoaderInstruction::InitializeBuffer => {
+            DecodedInstruction::new(LOADER, "initialize buffer")
+                .account("buffer", ix, 0)
+                .account("authority", ix, 1)
+        }
+        UpgradeableLoaderInstruction::Write { offset, bytes } => {
+            DecodedInstruction::new(LOADER, "write")
+                .account("buffer", ix, 0)
+                .account("authority", ix, 1)
+                .field("offset", offset)
+                .field("length", bytes.len())
+        }
+        UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len } => {
+            DecodedInstruction::new(LOADER, "deploy")
+                .account("payer", ix, 0)
+                .account("program data", ix, 1)
+                .account("program", ix, 2)
+                .account("buffer", ix, 3)
+                .account("authority", ix, 7)
+                .field("max data length", max_data_len)
+        }
+        UpgradeableLoaderInstruction::Upgrade => DecodedInstruction::new(LOADER, "upgrade")
+            .account("program data", ix, 0)
+            .account("program", ix, 1)
+            .account("buffer", ix, 2)
+            .account("spill", ix, 3)
+            .account("authority", ix, 6),
+        UpgradeableLoaderInstruction::SetAuthority => {
+            let decoded = DecodedInstruction::new(LOADER, "set authority")
+                .account("account", ix, 0)
+                .account("current authority", ix, 1);
+            match ix.accounts.get(2) {
+                Some(meta) => decoded.field("new authority", meta.pubkey),
+                None => decoded.field("new authority", "none"),
+            }
+        }
+        UpgradeableLoaderInstruction::Close => DecodedInstruction::new(LOADER, "close")
+            .account("account", ix, 0)
+            .account("recipient", ix, 1)
+            .account("authority", ix, 2)
+            .account("program", ix, 3),
+    };
+    Some(decoded)
+}
+
+/// Anchor's discriminator for the instruction handler `name`.
+fn sighash(name: &str) -> [u8; 8] {
+    let hash = solana_program::hash::hash(format!("global:{}", name).as_bytes());
+    let mut sighash = [0; 8];
+    sighash.copy_from_slice(&hash.to_bytes()[..8]);
+    sighash
+}
+
+fn decode_multisig(ix: &Instruction) -> Option<DecodedInstruction> {
+    use multisig_demo::instruction::{ChangeDefaultTtl, ChangeMembers, ChangeMinDelay, ChangeThreshold};
+    if ix.data.len() < 8 {
+        return None;
+    }
+    let (discriminator, data) = ix.data.split_at(8);
+    let decoded = if discriminator == sighash("change_threshold") {
+        let args = ChangeThreshold::try_from_slice(data).ok()?;
+        DecodedInstruction::new(MULTISIG, "change threshold")
+            .account("multisig wallet", ix, 0)
+            .field("threshold", args.threshold)
+    } else if discriminator == sighash("change_members") {
+        let args = ChangeMembers::try_from_slice(data).ok()?;
+        let decoded = DecodedInstruction::new(MULTISIG, "change members")
+            .account("multisig wallet", ix, 0);
+        args.members.iter().fold(decoded, |decoded, member| decoded.field("member", format!("{} (weight {})", member.key, member.weight)))
+    } else if discriminator == sighash("change_min_delay") {
+        let args = ChangeMinDelay::try_from_slice(data).ok()?;
+        DecodedInstruction::new(MULTISIG, "change min delay")
+            .account("multisig wallet", ix, 0)
+            .field("min delay", format!("{}s", args.min_delay))
+    } else if discriminator == sighash("change_default_ttl") {
+        let args = ChangeDefaultTtl::try_from_slice(data).ok()?;
+        DecodedInstruction::new(MULTISIG, "change default ttl")
+            .account("multisig wallet", ix, 0)
+            .field("default ttl", format!("{}s", args.default_ttl))
+    } else {
+        return None;
+    };
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_client::anchor_lang::InstructionData;
+
+    #[test]
+    fn decode_known_and_unknown_instructions() {
+        let registry = DecoderRegistry::default();
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+
+        let transfer = solana_sdk::system_instruction::transfer(&from, &to, 1_500_000_000);
+        let decoded = registry.decode(&transfer);
+        assert_eq!(decoded.to_string(), format!(
+            "System Program: transfer\n  from: {}\n  to: {}\n  lamports: 1500000000 (1.5 SOL)",
+            from, to));
+
+        let token = spl_token::instruction::transfer(
+            &spl_token::ID, &from, &to, &Pubkey::new_unique(), &[], 42).unwrap();
+        let decoded = registry.decode(&token);
+        assert_eq!(decoded.name, "transfer");
+        assert_eq!(decoded.fields[3].value, "42");
+
+        let memo = spl_memo::build_memo(b"hello", &[]);
+        assert_eq!(registry.decode(&memo).to_string(), "Memo: memo\n  memo: hello");
+
+        let wallet = Pubkey::new_unique();
+        let change_threshold = Instruction {
+            program_id: multisig_demo::ID,
+            accounts: vec![solana_program::instruction::AccountMeta::new(wallet, true)],
+            data: multisig_demo::instruction::ChangeThreshold { threshold: 3 }.data(),
+        };
+        assert_eq!(registry.decode(&change_threshold).to_string(), format!(
+            "Multisig: change threshold\n  multisig wallet: {}\n  threshold: 3", wallet));
+
+        let program = Pubkey::new_unique();
+        let unknown = Instruction::new_with_bytes(program, &[0xab, 0x01],
+            vec![solana_program::instruction::AccountMeta::new_readonly(from, false)]);
+        assert_eq!(registry.decode(&unknown).to_string(), format!(
+            "{}: unknown\n  account 0: {} (readonly)\n  data: ab01", program, from));
+    }
+}
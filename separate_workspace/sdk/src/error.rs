@@ -0,0 +1,225 @@
+use anchor_client::anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_client::anchor_lang::InstructionData;
+use multisig_demo::error::MultisigError;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use thiserror::Error;
+
+/// Every [MultisigError], indexed by its code minus the [ERROR_CODE_OFFSET].
+const MULTISIG_ERRORS: [MultisigError; 16] = [
+    MultisigError::InvalidThreshold,
+    MultisigError::DuplicateMembers,
+    MultisigError::TooFewMembers,
+    MultisigError::NotAMember,
+    MultisigError::InvalidConfigSeqno,
+    MultisigError::InvalidMultisigReference,
+    MultisigError::AlreadyApproved,
+    MultisigError::AlreadyUnapproved,
+    MultisigError::NotEnoughApprovals,
+    MultisigError::AlreadyExecuted,
+    MultisigError::NotCloseable,
+    MultisigError::AlreadyRejected,
+    MultisigError::AlreadyCancelled,
+    MultisigError::TimelockNotElapsed,
+    MultisigError::Expired,
+    MultisigError::ZeroWeight,
+];
+
+/// The [MultisigError] with the custom program error `code`, if any.
+pub fn multisig_error(code: u32) -> Option<MultisigError> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    MULTISIG_ERRORS.get(index as usize).copied()
+}
+
+/// Errors from sending transactions, with the program's logs attached when the
+/// cluster returned them, e.g. for a failed preflight simulation.
+///
+/// SDK functions return [anyhow::Result], so branch on these with
+/// [anyhow::Error::downcast_ref].
+#[derive(Debug, Error)]
+pub enum SdkError {
+    /// The multisig program failed the instruction at `index` with one of its own errors,
+    /// possibly from an administration instruction that an executed transaction invokes.
+    #[error("instruction {index} failed: {error}")]
+    Multisig {
+        index: u8,
+        error: MultisigError,
+        logs: Vec<String>,
+    },
+    /// The transaction failed for any other reason, including errors of the
+    /// instructions that the multisig invokes during execution. Without logs,
+    /// custom errors of `execute` are kept here, since their program is unknown.
+    #[error("transaction failed: {error}")]
+    Transaction {
+        error: TransactionError,
+        logs: Vec<String>,
+    },
+    /// The transaction didn't reach the desired commitment level in time.
+    /// It may still land later.
+    #[error("timed out waiting for transaction {signature}")]
+    Timeout { signature: Signature },
+    /// The transaction's blockhash expired before it landed, and it couldn't be
+    /// signed over a newer one.
+    #[error("blockhash of transaction {signature} expired before it landed")]
+    BlockhashExpired { signature: Signature },
+    /// The transaction couldn't be submitted, e.g. because the cluster is unreachable.
+    #[error(transparent)]
+    Client(ClientError),
+}
+
+impl SdkError {
+    /// Interpret an error from sending `tx`. Custom errors are only attributed to
+    /// the multisig program if it raised them, see [SdkError::from_transaction_error].
+    pub fn from_client_error(err: ClientError, tx: &Transaction) -> Self {
+        match err.get_transaction_error() {
+            Some(error) => SdkError::from_transaction_error(error, tx, preflight_logs(&err)),
+            None => SdkError::Client(err),
+        }
+    }
+
+    /// Interpret the `error` that failed `tx`. A custom error is only a multisig
+    /// error if the multisig program raised it, which the `logs` tell by the first
+    /// program to fail. Without them, it must be the failing instruction's program,
+    /// and the instruction mustn't be `execute`, which may have failed in a program
+    /// that it invokes.
+    pub fn from_transaction_error(error: TransactionError, tx: &Transaction, logs: Vec<String>) -> Self {
+        if let TransactionError::InstructionError(index, InstructionError::Custom(code)) = error {
+            let is_multisig = match failed_program(&logs) {
+                Some(program) => program == multisig_demo::ID,
+                None => tx.message.instructions
+                    .get(index as usize)
+                    .map(|ix| ix.program_id(&tx.message.account_keys) == &multisig_demo::ID
+                        && !ix.data.starts_with(&multisig_demo::instruction::Execute {}.data()))
+                    .unwrap_or(false),
+            };
+            if let Some(error) = multisig_error(code).filter(|_| is_multisig) {
+                return SdkError::Multisig { index, error, logs };
+            }
+        }
+        SdkError::Transaction { error, logs }
+    }
+
+    /// The [MultisigError] that failed the transaction, if any.
+    pub fn multisig_error(&self) -> Option<MultisigError> {
+        match self {
+            SdkError::Multisig { error, .. } => Some(*error),
+            _ => None,
+        }
+    }
+
+    /// Program logs of the failed transaction, if the cluster returned them.
+    pub fn logs(&self) -> &[String] {
+        match self {
+            SdkError::Multisig { logs, .. } | SdkError::Transaction { logs, .. } => logs,
+            SdkError::Timeout { .. } | SdkError::BlockhashExpired { .. } | SdkError::Client(_) => &[],
+        }
+    }
+}
+
+/// The program that raised the error of a failed transaction. Its failure is
+/// logged first, before those of the programs that invoked it.
+fn failed_program(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().find_map(|log| match log.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["Program", program, "failed:", ..] => program.parse().ok(),
+        _ => None,
+    })
+}
+
+fn preflight_logs(err: &ClientError) -> Vec<String> {
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) => result.logs.clone().unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_response::RpcSimulateTransactionResult;
+    use solana_sdk::instruction::Instruction;
+    use solana_sdk::message::Message;
+
+    fn preflight_failure(err: TransactionError, logs: Vec<String>) -> ClientError {
+        RpcError::RpcResponseError {
+            code: -32002,
+            message: "Transaction simulation failed".to_string(),
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(
+                RpcSimulateTransactionResult {
+                    err: Some(err),
+                    logs: Some(logs),
+                    accounts: None,
+                    units_consumed: None,
+                },
+            ),
+        }.into()
+    }
+
+    #[test]
+    fn multisig_error_codes() {
+        for (i, error) in MULTISIG_ERRORS.iter().enumerate() {
+            assert_eq!(*error as usize, i);
+            assert_eq!(multisig_error(ERROR_CODE_OFFSET + i as u32), Some(*error));
+        }
+        // Fails once a new variant is added to the program but not to the table.
+        assert_eq!(MULTISIG_ERRORS.len() - 1, MultisigError::ZeroWeight as usize);
+        assert_eq!(multisig_error(1), None);
+        assert_eq!(multisig_error(ERROR_CODE_OFFSET + MULTISIG_ERRORS.len() as u32), None);
+    }
+
+    #[test]
+    fn classify_preflight_failures() {
+        let other_program = Pubkey::new_unique();
+        let tx = Transaction::new_unsigned(Message::new(&[
+            Instruction::new_with_bytes(other_program, &[], vec![]),
+            Instruction::new_with_bytes(multisig_demo::ID, &[], vec![]),
+        ], Some(&Pubkey::new_unique())));
+        let logs = vec!["Program log: AnchorError".to_string()];
+
+        let err = preflight_failure(
+            TransactionError::InstructionError(1, InstructionError::Custom(6006)), logs.clone());
+        let err = SdkError::from_client_error(err, &tx);
+        assert_eq!(err.multisig_error(), Some(MultisigError::AlreadyApproved));
+        assert_eq!(err.logs(), logs.as_slice());
+
+        // The same code from another program isn't a multisig error.
+        let err = preflight_failure(
+            TransactionError::InstructionError(0, InstructionError::Custom(6006)), logs.clone());
+        assert!(matches!(SdkError::from_client_error(err, &tx), SdkError::Transaction { .. }));
+
+        let err = ClientError::from(ClientErrorKind::Custom("unreachable".to_string()));
+        assert!(matches!(SdkError::from_client_error(err, &tx), SdkError::Client(_)));
+    }
+
+    #[test]
+    fn classify_execute_failures() {
+        let execute = Instruction::new_with_bytes(
+            multisig_demo::ID, &multisig_demo::instruction::Execute {}.data(), vec![]);
+        let tx = Transaction::new_unsigned(Message::new(&[execute], Some(&Pubkey::new_unique())));
+        let error = TransactionError::InstructionError(0, InstructionError::Custom(6006));
+        let failed = |program: &Pubkey| vec![
+            format!("Program {} invoke [1]", multisig_demo::ID),
+            format!("Program {} invoke [2]", program),
+            format!("Program {} failed: custom program error: 0x1776", program),
+            format!("Program {} failed: custom program error: 0x1776", multisig_demo::ID),
+        ];
+
+        // A program invoked by the executed transaction raised the error.
+        let err = SdkError::from_transaction_error(error.clone(), &tx, failed(&Pubkey::new_unique()));
+        assert!(matches!(err, SdkError::Transaction { .. }));
+
+        // An administration instruction of the multisig raised it.
+        let err = SdkError::from_transaction_error(error.clone(), &tx, failed(&multisig_demo::ID));
+        assert_eq!(err.multisig_error(), Some(MultisigError::AlreadyApproved));
+
+        // Without logs, the program that raised it is unknown.
+        let err = SdkError::from_transaction_error(error, &tx, vec![]);
+        assert!(matches!(err, SdkError::Transaction { .. }));
+    }
+}
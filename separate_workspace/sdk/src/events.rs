@@ -0,0 +1,163 @@
+use anchor_client::anchor_lang::{AnchorDeserialize, Discriminator};
+use multisig_demo::events::*;
+use solana_program::pubkey::Pubkey;
+
+/// Prefix of the log lines written by `emit!`, followed by the base64 encoded event.
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Any of the events that the program emits on a state transition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MultisigEvent {
+    MultisigCreated(MultisigCreated),
+    TransactionProposed(TransactionProposed),
+    TransactionApproved(TransactionApproved),
+    TransactionUnapproved(TransactionUnapproved),
+    TransactionRejected(TransactionRejected),
+    TransactionExecuted(TransactionExecuted),
+    TransactionClosed(TransactionClosed),
+    ThresholdChanged(ThresholdChanged),
+    MinDelayChanged(MinDelayChanged),
+    DefaultTtlChanged(DefaultTtlChanged),
+    MembersChanged(MembersChanged),
+}
+
+macro_rules! decode_event {
+    ($discriminator:expr, $data:expr, $($event:ident),+ $(,)?) => {
+        $(
+            if $discriminator == $event::discriminator() {
+                return $event::deserialize(&mut $data).ok().map(MultisigEvent::$event);
+            }
+        )+
+    };
+}
+
+impl MultisigEvent {
+    /// Decode an event from its discriminator followed by its serialized fields,
+    /// or [None] if the data isn't one of the program's events.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (discriminator, mut data) = data.split_at(8);
+        decode_event!(discriminator, data,
+            MultisigCreated,
+            TransactionProposed,
+            TransactionApproved,
+            TransactionUnapproved,
+            TransactionRejected,
+            TransactionExecuted,
+            TransactionClosed,
+            ThresholdChanged,
+            MinDelayChanged,
+            DefaultTtlChanged,
+            MembersChanged,
+        );
+        None
+    }
+}
+
+/// Extract the program's events from the logs of a transaction, in the order
+/// they were emitted. Invocations are tracked so that data logged by other
+/// programs is skipped, while events of the administration instructions that
+/// an executed transaction invokes are still included.
+pub fn parse_events(logs: &[String]) -> Vec<MultisigEvent> {
+    let mut invocations: Vec<Pubkey> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if invocations.last() != Some(&multisig_demo::ID) {
+                continue;
+            }
+            if let Some(event) = base64::decode(data).ok()
+                .and_then(|data| MultisigEvent::decode(&data)) {
+                events.push(event);
+            }
+            continue;
+        }
+        let words: Vec<&str> = log.split_whitespace().collect();
+        match words.as_slice() {
+            ["Program", program, "invoke", _depth] => {
+                if let Ok(program) = program.parse() {
+                    invocations.push(program);
+                }
+            }
+            ["Program", _, "success"] | ["Program", _, "failed:", ..] => {
+                invocations.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_client::anchor_lang::Event;
+    use super::*;
+
+    fn invoke(program: &Pubkey, depth: usize) -> String {
+        format!("Program {} invoke [{}]", program, depth)
+    }
+
+    fn success(program: &Pubkey) -> String {
+        format!("Program {} success", program)
+    }
+
+    fn data(event: &impl Event) -> String {
+        format!("{}{}", PROGRAM_DATA, base64::encode(event.data()))
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let event = TransactionApproved {
+            multisig_wallet: Pubkey::new_unique(),
+            transaction: Pubkey::new_unique(),
+            member: Pubkey::new_unique(),
+            approved_weight: 2,
+            timestamp: 1_000,
+        };
+        assert_eq!(MultisigEvent::decode(&event.data()),
+            Some(MultisigEvent::TransactionApproved(event)));
+        assert_eq!(MultisigEvent::decode(&[0; 8]), None);
+        assert_eq!(MultisigEvent::decode(&[]), None);
+    }
+
+    #[test]
+    fn parse_events_of_an_execution() {
+        let multisig_wallet = Pubkey::new_unique();
+        let transaction = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let changed = ThresholdChanged {
+            multisig_wallet,
+            threshold: 1,
+            config_seqno: 1,
+        };
+        let executed = TransactionExecuted {
+            multisig_wallet,
+            transaction,
+            executor: Pubkey::new_unique(),
+            timestamp: 1_000,
+        };
+        let logs = vec![
+            invoke(&multisig_demo::ID, 1),
+            "Program log: Instruction: Execute".to_string(),
+            // Data logged by other programs is skipped, even if it decodes.
+            invoke(&other_program, 2),
+            data(&changed),
+            success(&other_program),
+            // Administration instructions emit events from within the execution.
+            invoke(&multisig_demo::ID, 2),
+            "Program log: Instruction: ChangeThreshold".to_string(),
+            data(&changed),
+            format!("Program {} consumed 1000 of 200000 compute units", multisig_demo::ID),
+            success(&multisig_demo::ID),
+            format!("{}not base64", PROGRAM_DATA),
+            data(&executed),
+            success(&multisig_demo::ID),
+        ];
+        assert_eq!(parse_events(&logs), vec![
+            MultisigEvent::ThresholdChanged(changed),
+            MultisigEvent::TransactionExecuted(executed),
+        ]);
+    }
+}
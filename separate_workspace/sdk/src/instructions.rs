@@ -3,304 +3,383 @@ use anchor_client::anchor_lang::InstructionData;
 use anchor_client::anchor_lang::ToAccountMetas;
 use anchor_client::anchor_lang::AccountDeserialize;
 use anchor_client::anchor_lang::prelude::System;
-use anyhow::Result;
-use solana_client::client_error::ClientErrorKind;
+use std::ops::Deref;
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{anyhow, Result};
 use solana_sdk::instruction::Instruction;
 use solana_client::rpc_client::RpcClient;
-use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
-use solana_program::instruction::AccountMeta;
 use solana_program::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::signer::Signer;
 use solana_sdk::transaction::Transaction;
 use multisig_demo::state::{MultisigTransaction, MultisigWallet};
 use crate::pda::{find_multisig_transaction_address, find_multisig_wallet_address};
+use crate::builders::MemberInstructions;
+use crate::members::{sort_members, Member};
+use crate::client::MultisigClient;
+use crate::simulation::{simulate_instructions, Simulation};
+use crate::transaction::{build_transaction, send_with_confirmation, Confirmation, TransactionConfig};
 
-pub fn fetch_transaction(addr: &Pubkey, client: &RpcClient) -> Result<MultisigTransaction> {
-    let act_data = client.get_account_data(&addr)?;
+pub fn fetch_transaction(addr: &Pubkey, client: &dyn MultisigClient) -> Result<MultisigTransaction> {
+    let act_data = fetch_account_data(addr, client)?;
     let tx = MultisigTransaction::try_deserialize(&mut act_data.as_slice())?;
     Ok(tx)
 }
 
-/// Create a new multisig wallet
-pub fn new_multisig_rpc(
+pub fn fetch_wallet(addr: &Pubkey, client: &dyn MultisigClient) -> Result<MultisigWallet> {
+    let act_data = fetch_account_data(addr, client)?;
+    let wallet = MultisigWallet::try_deserialize(&mut act_data.as_slice())?;
+    Ok(wallet)
+}
+
+fn fetch_account_data(addr: &Pubkey, client: &dyn MultisigClient) -> Result<Vec<u8>> {
+    let account = client.get_account(addr)?
+        .ok_or_else(|| anyhow!("account {} not found", addr))?;
+    Ok(account.data)
+}
+
+/// Fetch every [MultisigTransaction] proposed on a multisig wallet
+/// that still exists on-chain, i.e. hasn't been closed.
+/// Addresses are derived for every nonce below `tx_nonce`.
+pub fn fetch_wallet_transactions(
+    multisig_wallet: &Pubkey,
+    tx_nonce: u64,
+    client: &dyn MultisigClient,
+) -> Result<Vec<(Pubkey, MultisigTransaction)>> {
+    let addresses: Vec<Pubkey> = (0..tx_nonce)
+        .map(|nonce| find_multisig_transaction_address(multisig_wallet, nonce))
+        .collect();
+    let mut transactions = vec![];
+    // The RPC limits how many accounts can be fetched at once.
+    for chunk in addresses.chunks(100) {
+        let accounts = client.get_multiple_accounts(chunk)?;
+        for (address, account) in chunk.iter().zip(accounts) {
+            if let Some(account) = account {
+                let data = MultisigTransaction::try_deserialize(
+                    &mut account.data.as_slice())?;
+                transactions.push((*address, data));
+            }
+        }
+    }
+    Ok(transactions)
+}
+
+/// Create an instruction that uses the [Approval] accounts,
+/// i.e. [Approve], [Unapprove] or [Reject] with their respective `data`.
+/// It doesn't depend on the multisig wallet's data, so it can be built offline.
+pub fn approval_ix(
+    data: Vec<u8>,
+    multisig_wallet: Pubkey,
+    transaction: Pubkey,
+    member: Pubkey,
+) -> Instruction {
+    let acts = multisig_demo::accounts::Approval {
+        member,
+        transaction,
+        multisig_wallet,
+    };
+    Instruction {
+        data,
+        accounts: acts.to_account_metas(None),
+        program_id: multisig_demo::ID,
+    }
+}
+
+/// Create the instruction to make a new multisig wallet, derived from `base`.
+/// The `payer` funds the wallet's rent.
+pub fn new_multisig_ix(
     threshold: u16,
-    members: Vec<Pubkey>,
-    client: &RpcClient,
-    payer: &dyn Signer,
-    base: Option<&dyn Signer>,
-) -> Result<Signature> {
+    mut members: Vec<Member>,
+    base: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    sort_members(&mut members);
     let ix = multisig_demo::instruction::NewMultisig {
         threshold,
         members,
     };
-    let maybe_key = Keypair::new();
-    let base = base.unwrap_or(&maybe_key);
-    let multisig_wallet = find_multisig_wallet_address(&base.pubkey());
     let acts = multisig_demo::accounts::NewMultisig {
-        base: base.pubkey(),
-        payer: payer.pubkey(),
-        multisig_wallet,
+        base: *base,
+        payer: *payer,
+        multisig_wallet: find_multisig_wallet_address(base),
         system_program: System::id(),
     };
-    let ix = Instruction {
+    Instruction {
         data: ix.data(),
         accounts: acts.to_account_metas(None),
         program_id: multisig_demo::ID,
+    }
+}
+
+/// Create a new multisig wallet.
+/// The `payer` funds the wallet's rent, and transaction fees are paid by
+/// `fee_payer` if given, or `payer` otherwise.
+pub fn new_multisig_rpc(
+    threshold: u16,
+    members: Vec<Member>,
+    client: &dyn MultisigClient,
+    payer: &dyn Signer,
+    base: Option<&dyn Signer>,
+    fee_payer: Option<&dyn Signer>,
+    confirmation: &Confirmation,
+) -> Result<Signature> {
+    let maybe_key = Keypair::new();
+    let base = base.unwrap_or(&maybe_key);
+    let ix = new_multisig_ix(threshold, members, &base.pubkey(), &payer.pubkey());
+    let config = TransactionConfig {
+        signers: vec![payer, base],
+        ..Default::default()
     };
-    let tx = Transaction::new_signed_with_payer(
-        &[ix],
-        Some(&payer.pubkey()),
-        &[payer, base],
-        client.get_latest_blockhash()?
-    );
-    Ok(client.send_transaction(&tx)?)
+    let build = || build_transaction(client, std::slice::from_ref(&ix), fee_payer.unwrap_or(payer), &config);
+    send_with_confirmation(client, &build, confirmation, &|_| {})
 }
 
 /// Association between a signer and a multisig wallet.
 /// This allows for abstracting away many implementation details
 /// for constructing transaction instructions and RPC calls.
-pub struct MultisigMember {
+/// The instruction builders are available through [Deref] to [MemberInstructions].
+/// Calls go through an [RpcClient] unless another [MultisigClient] is given.
+pub struct MultisigMember<C = RpcClient> {
     member: Box<dyn Signer>,
-    multisig_address: Pubkey,
-    multisig_data: MultisigWallet,
-    client: RpcClient,
+    /// Pays transaction fees instead of the member, if set.
+    fee_payer: Option<Box<dyn Signer>>,
+    /// How long to wait for sent transactions.
+    confirmation: Confirmation,
+    instructions: MemberInstructions,
+    client: C,
 }
 
-impl MultisigMember {
+impl<C: MultisigClient> MultisigMember<C> {
     /// This constructor pulls the multisig wallet metadata from on-chain.
     pub fn try_new(
         member: Box<dyn Signer>,
         multisig_address: Pubkey,
-        client: RpcClient,
+        client: C,
     ) -> Result<Self> {
-        let act_data = client.get_account_data(&multisig_address)?;
-        let multisig_data = MultisigWallet::try_deserialize(&mut act_data.as_slice())?;
-        Ok(Self {
+        let multisig_data = fetch_wallet(&multisig_address, &client)?;
+        Ok(Self::new(member, multisig_address, multisig_data, client))
+    }
+
+    /// This constructor uses already fetched multisig wallet metadata.
+    pub fn new(
+        member: Box<dyn Signer>,
+        multisig_address: Pubkey,
+        multisig_data: MultisigWallet,
+        client: C,
+    ) -> Self {
+        Self {
+            instructions: MemberInstructions {
+                member: member.pubkey(),
+                multisig_address,
+                multisig_data,
+            },
             member,
-            multisig_address,
-            multisig_data,
+            fee_payer: None,
+            confirmation: Confirmation::default(),
             client,
-        })
+        }
+    }
+
+    /// Pay transaction fees with `fee_payer` instead of the member,
+    /// e.g. for members on hardware wallets that hold no SOL.
+    /// Note that the member still pays the rent of the proposals it creates.
+    pub fn with_fee_payer(mut self, fee_payer: Box<dyn Signer>) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    /// Wait for sent transactions according to `confirmation`, instead of
+    /// until they are confirmed.
+    pub fn with_confirmation(mut self, confirmation: Confirmation) -> Self {
+        self.confirmation = confirmation;
+        self
     }
 
     /// Pull data from on-chain, mostly just in case the `tx_nonce` has
     /// incremented.
     pub fn refresh_wallet(&mut self) -> Result<()> {
-        let act_data = self.client.get_account_data(&self.multisig_address)?;
-        self.multisig_data = MultisigWallet::try_deserialize(&mut act_data.as_slice())?;
+        self.instructions.multisig_data = fetch_wallet(&self.multisig_address, &self.client)?;
         Ok(())
     }
 
-    /// Returns the pubkey of the next proposal produced on this multisig wallet,
-    /// which updates every [NewTransaction] instruction with the increment of
-    /// the `member_set_seqno`.
-    pub fn next_transaction_pubkey(&self) -> Pubkey {
-        find_multisig_transaction_address(
-            &self.multisig_address, self.multisig_data.tx_nonce)
+    /// Fetch every [MultisigTransaction] proposed on this multisig wallet
+    /// that still exists on-chain, i.e. hasn't been closed.
+    pub fn fetch_transactions(&self) -> Result<Vec<(Pubkey, MultisigTransaction)>> {
+        fetch_wallet_transactions(
+            &self.multisig_address, self.multisig_data.tx_nonce, &self.client)
+    }
+
+    /// RPC call to propose a new [MultisigTransaction], optionally approving it as well.
+    /// `ttl` is zero for no expiry, or [None] for the wallet's default, which also
+    /// applies to the `propose_change_*` calls.
+    /// Returns the address of the proposal, and the transaction signature.
+    pub fn propose(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let (proposal, ixs) = self.propose_ixs(instructions, vault_index, ttl, approve);
+        let signature = self.send_instructions(&ixs)?;
+        // Keep the nonce current, so that consecutive proposals get the right address.
+        self.instructions.multisig_data.tx_nonce += 1;
+        Ok((proposal, signature))
     }
 
     /// RPC call to propose a change of the approval threshold.
-    pub fn propose_change_threshold(&self, threshold: u16) -> Result<Signature> {
-        let ix = multisig_demo::instruction::ChangeThreshold {
-            threshold
-        };
-        let acts = multisig_demo::accounts::Administration {
-            multisig_wallet: self.multisig_address.clone()
-        };
-        let ix = Instruction {
-            data: ix.data(),
-            accounts: acts.to_account_metas(None),
-            program_id: multisig_demo::ID,
-        };
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)?)
+    pub fn propose_change_threshold(
+        &mut self,
+        threshold: u16,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_threshold_ix(threshold);
+        self.propose(vec![ix], 0, ttl, approve)
     }
 
-    /// RPC call to propose a change of the member set.
-    pub fn propose_change_members(&self, members: Vec<Pubkey>) -> Result<Signature> {
-        let ix = multisig_demo::instruction::ChangeMembers {
-            members
-        };
-        let acts = multisig_demo::accounts::Administration {
-            multisig_wallet: self.multisig_address.clone()
-        };
-        let ix = Instruction {
-            data: ix.data(),
-            accounts: acts.to_account_metas(None),
-            program_id: multisig_demo::ID,
-        };
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)?)
+    /// RPC call to propose a change of the timelock between a transaction
+    /// reaching the approval threshold and its execution.
+    pub fn propose_change_min_delay(
+        &mut self,
+        min_delay: u32,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_min_delay_ix(min_delay);
+        self.propose(vec![ix], 0, ttl, approve)
     }
 
-    /// Create the instruction to create a new [MultisigTransaction].
-    pub fn new_transaction_ix(&mut self, instructions: Vec<Instruction>) -> Instruction {
-        let ix = multisig_demo::instruction::NewTransaction {
-            instructions: instructions.into_iter().map(|ix| ix.into()).collect(),
-        };
-        let transaction = self.next_transaction_pubkey();
-        let acts = multisig_demo::accounts::NewTransaction {
-            proposer: self.member.pubkey(),
-            multisig_wallet: self.multisig_address.clone(),
-            transaction,
-            system_program: System::id(),
-        };
-        Instruction {
-            data: ix.data(),
-            accounts: acts.to_account_metas(None),
-            program_id: multisig_demo::ID,
-        }
+    /// RPC call to propose a change of the default time-to-live of new transactions.
+    pub fn propose_change_default_ttl(
+        &mut self,
+        default_ttl: u32,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_default_ttl_ix(default_ttl);
+        self.propose(vec![ix], 0, ttl, approve)
     }
 
-    /// RPC call to create a new transaction proposal under this object's
-    /// multisig wallet.
-    pub fn new_transaction_rpc(&mut self, instructions: Vec<Instruction>) -> Result<Signature> {
-        let ix = self.new_transaction_ix(instructions);
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)?)
+    /// RPC call to propose a change of the member set.
+    pub fn propose_change_members(
+        &mut self,
+        members: Vec<Member>,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_members_ix(members);
+        self.propose(vec![ix], 0, ttl, approve)
     }
 
-    /// Create the instruction to approve a [MultisigTransaction].
-    pub fn approve_ix(&self, transaction: Pubkey) -> Instruction {
-        let ix = multisig_demo::instruction::Approve;
-        let acts = multisig_demo::accounts::Approval {
-            member: self.member.pubkey(),
-            transaction,
-            multisig_wallet: self.multisig_address.clone(),
-        };
-        Instruction {
-            data: ix.data(),
-            accounts: acts.to_account_metas(None),
-            program_id: multisig_demo::ID,
-        }
+    /// RPC call to create a new transaction proposal under this object's
+    /// multisig wallet.
+    pub fn new_transaction_rpc(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+    ) -> Result<Signature> {
+        let ix = self.new_transaction_ix(instructions, vault_index, ttl);
+        self.send_instructions(&[ix])
     }
 
     /// RPC call to approve a [MultisigTransaction].
     pub fn approve_rpc(&self, transaction: Pubkey) -> Result<Signature> {
         let ix = self.approve_ix(transaction);
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)
-            .map_err(|e| maybe_print_preflight_simulation_logs(e))
-            ?)
-    }
-
-    /// Create the instruction to cancel approval of a [MultisigTransaction].
-    pub fn unapprove_ix(&self, transaction: Pubkey) -> Instruction {
-        let ix = multisig_demo::instruction::Unapprove;
-        let acts = multisig_demo::accounts::Approval {
-            member: self.member.pubkey(),
-            transaction,
-            multisig_wallet: self.multisig_address.clone(),
-        };
-        Instruction {
-            data: ix.data(),
-            accounts: acts.to_account_metas(None),
-            program_id: multisig_demo::ID,
-        }
+        self.send_instructions(&[ix])
     }
 
     /// RPC call to cancel approval of a [MultisigTransaction].
     pub fn unapprove_rpc(&self, transaction: Pubkey) -> Result<Signature> {
         let ix = self.unapprove_ix(transaction);
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)
-            .map_err(|e| maybe_print_preflight_simulation_logs(e))
-            ?)
-    }
-
-    /// Create the instruction to execute a [MultisigTransaction].
-    pub fn execute_ix(&self, transaction: Pubkey, data: MultisigTransaction) -> Instruction {
-        let ix = multisig_demo::instruction::Execute;
-        let acts = multisig_demo::accounts::Approval {
-            member: self.member.pubkey(),
-            transaction,
-            multisig_wallet: self.multisig_address.clone(),
-        };
-        let mut act_metas = acts.to_account_metas(None);
-        data.instructions
-            .into_iter()
-            .for_each(|ix| {
-                ix.keys
-                    .into_iter()
-                    .for_each(|key| {
-                        if key.pubkey != self.multisig_address {
-                            act_metas.push(key.into());
-                        }
-                    });
-                act_metas.push(AccountMeta {
-                    pubkey: ix.program_id,
-                    is_signer: false,
-                    is_writable: false
-                });
-            });
-        act_metas.push(AccountMeta {
-            pubkey: self.multisig_address,
-            is_signer: false,
-            is_writable: false
-        });
-        Instruction {
-            data: ix.data(),
-            accounts: act_metas,
-            program_id: multisig_demo::ID,
-        }
+        self.send_instructions(&[ix])
+    }
+
+    /// RPC call to reject a [MultisigTransaction].
+    pub fn reject_rpc(&self, transaction: Pubkey) -> Result<Signature> {
+        let ix = self.reject_ix(transaction);
+        self.send_instructions(&[ix])
     }
 
     /// RPC call to execute a [MultisigTransaction].
     pub fn execute_rpc(&self, transaction: Pubkey) -> Result<Signature> {
         let data = fetch_transaction(&transaction, &self.client)?;
         let ix = self.execute_ix(transaction, data);
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)
-            .map_err(|e| maybe_print_preflight_simulation_logs(e))
-            ?)
+        self.send_instructions(&[ix])
+    }
+
+    /// Simulate executing a [MultisigTransaction], without verifying signatures.
+    /// If it can't be executed yet, e.g. because the threshold isn't met, its
+    /// instructions are simulated on their own, as if signed by the vault.
+    /// The balances of every writable account of the proposal are reported.
+    pub fn simulate_execute(&self, transaction: Pubkey) -> Result<Simulation> {
+        let data = fetch_transaction(&transaction, &self.client)?;
+        // Judged by the local clock, which may be off from the cluster's by a few seconds.
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let (via_execute, instructions, addresses) =
+            self.simulate_execute_ixs(transaction, data, now);
+        let mut simulation = simulate_instructions(
+            &self.client, &instructions, &self.fee_payer().pubkey(), &addresses)?;
+        simulation.via_execute = via_execute;
+        Ok(simulation)
+    }
+
+    /// RPC call to close a [MultisigTransaction] that can no longer be executed,
+    /// see [MultisigTransaction::is_closeable].
+    pub fn close_transaction_rpc(&self, transaction: Pubkey) -> Result<Signature> {
+        let data = fetch_transaction(&transaction, &self.client)?;
+        let ix = self.close_transaction_ix(transaction, data.proposer);
+        self.send_instructions(&[ix])
+    }
+
+    /// RPC call with arbitrary instructions, signed by the member,
+    /// and paid for by the fee payer. Waits for the transaction as configured
+    /// with [MultisigMember::with_confirmation].
+    pub fn send_instructions(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let build = || self.build_transaction(instructions, &TransactionConfig::default());
+        send_with_confirmation(&self.client, &build, &self.confirmation, &|_| {})
+    }
+
+    /// Assemble a transaction signed by this member and paid for by the fee payer,
+    /// without sending it. See [build_transaction].
+    pub fn build_transaction(
+        &self,
+        instructions: &[Instruction],
+        config: &TransactionConfig,
+    ) -> Result<Transaction> {
+        let mut config = config.clone();
+        config.signers.push(self.member.as_ref());
+        build_transaction(&self.client, instructions, self.fee_payer(), &config)
+    }
+
+    /// The signer acting as this multisig member.
+    pub fn signer(&self) -> &dyn Signer {
+        self.member.as_ref()
+    }
+
+    /// The signer paying transaction fees, which defaults to the member.
+    pub fn fee_payer(&self) -> &dyn Signer {
+        self.fee_payer.as_deref().unwrap_or(self.member.as_ref())
+    }
+
+    /// The client used by this member.
+    pub fn client(&self) -> &C {
+        &self.client
     }
 
     /// RPC call with a compound transaction for convenience,
     /// issuing both the [CreateTransaction] and [Approve] instructions.
-    pub fn create_and_approve_tx(&mut self, instructions: Vec<Instruction>) -> Result<Signature> {
-        let ix = self.new_transaction_ix(instructions);
+    pub fn create_and_approve_tx(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+    ) -> Result<Signature> {
+        let ix = self.new_transaction_ix(instructions, vault_index, ttl);
         let transaction = self.next_transaction_pubkey();
         let ix2 = self.approve_ix(transaction);
-        let tx = Transaction::new_signed_with_payer(
-            &[ix, ix2],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)
-            .map_err(|e| maybe_print_preflight_simulation_logs(e))
-            ?)
+        self.send_instructions(&[ix, ix2])
     }
 
     /// RPC call with a compound transaction for convenience,
@@ -309,37 +388,16 @@ impl MultisigMember {
         let ix = self.approve_ix(transaction);
         let data = fetch_transaction(&transaction, &self.client)?;
         let ix2 = self.execute_ix(transaction, data);
-        let tx = Transaction::new_signed_with_payer(
-            &[ix, ix2],
-            Some(&self.member.pubkey()),
-            &[self.member.as_ref()],
-            self.client.get_latest_blockhash()?
-        );
-        Ok(self.client.send_transaction(&tx)
-            .map_err(|e| maybe_print_preflight_simulation_logs(e))
-            ?)
+        self.send_instructions(&[ix, ix2])
     }
 }
 
-/// Prints the transaction logs for failed preflight simulations.
-/// Otherwise just prints the error.
-/// Returns the error back out for any further desired processing.
-pub fn maybe_print_preflight_simulation_logs(
-    err: solana_client::client_error::ClientError
-) -> solana_client::client_error::ClientError {
-    if let ClientErrorKind::RpcError(err) = &err.kind {
-        if let RpcError::RpcResponseError { data, .. } = err {
-            // print the transaction logs for a failed pre-flight simulation
-            if let RpcResponseErrorData::SendTransactionPreflightFailure(
-                result
-            ) = data {
-                if let Some(logs) = &result.logs {
-                    logs.iter().for_each(|e| println!("{}", e))
-                }
-            }
-        }
+impl<C> Deref for MultisigMember<C> {
+    type Target = MemberInstructions;
+
+    fn deref(&self) -> &MemberInstructions {
+        &self.instructions
     }
-    err
 }
 
 #[cfg(test)]
@@ -360,26 +418,98 @@ mod tests {
         let client = RpcClient::new_mock("succeeds");
         let data = MultisigWallet {
             base,
-            members: vec![signer.pubkey()],
+            members: vec![Member { key: signer.pubkey(), weight: 1 }],
             threshold: 1,
+            min_delay: 0,
+            default_ttl: 0,
             tx_nonce: 0,
-            member_set_seqno: 0,
+            config_seqno: 0,
             bump,
         };
-        let mut member = MultisigMember {
-            member: signer,
-            multisig_address: multisig,
-            multisig_data: data,
-            client
-        };
+        let member = MultisigMember::new(signer, multisig, data, client);
         let transaction = find_multisig_transaction_address(
             &multisig, 0,
         );
-        let _ix = member.new_transaction_ix(vec![]);
+        let _ix = member.new_transaction_ix(vec![], 0, None);
         let _ix = member.approve_ix(transaction.clone());
         let _ix = member.unapprove_ix(transaction.clone());
+        let _ix = member.reject_ix(transaction);
+        let _ix = member.close_transaction_ix(transaction, Pubkey::new_unique());
         let _ix = member.execute_ix(transaction.clone(),
             MultisigTransaction::default(),
         );
     }
+
+    #[test]
+    fn execute_ix_does_not_require_vault_signature() {
+        let signer = Box::new(Keypair::new());
+        let multisig = Pubkey::new_unique();
+        let member = MultisigMember::new(
+            signer, multisig, MultisigWallet::default(), RpcClient::new_mock("succeeds"));
+        let vault = member.vault_pubkey(1);
+        let transfer = solana_sdk::system_instruction::transfer(
+            &vault, &Pubkey::new_unique(), 1);
+        let data = MultisigTransaction {
+            instructions: vec![transfer.into()],
+            vault_index: 1,
+            ..Default::default()
+        };
+        let ix = member.execute_ix(Pubkey::new_unique(), data);
+        let vault_meta = ix.accounts
+            .iter()
+            .find(|meta| meta.pubkey == vault)
+            .unwrap();
+        assert!(!vault_meta.is_signer);
+        assert!(vault_meta.is_writable);
+    }
+
+    #[test]
+    fn propose_ixs_embeds_administration_ix() {
+        use anchor_client::anchor_lang::AnchorDeserialize;
+        let signer = Box::new(Keypair::new());
+        let multisig = Pubkey::new_unique();
+        let member = MultisigMember::new(
+            signer, multisig, MultisigWallet::default(), RpcClient::new_mock("succeeds"));
+        let admin_ix = member.change_threshold_ix(2);
+        let (proposal, ixs) = member.propose_ixs(vec![admin_ix], 0, Some(60), true);
+        assert_eq!(proposal, find_multisig_transaction_address(&multisig, 0));
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[1], member.approve_ix(proposal));
+
+        let new_transaction = multisig_demo::instruction::NewTransaction::try_from_slice(
+            &ixs[0].data[8..]).unwrap();
+        assert_eq!(new_transaction.ttl, Some(60));
+        let embedded = &new_transaction.instructions[0];
+        assert_eq!(embedded.program_id, multisig_demo::ID);
+        assert_eq!(embedded.data,
+            multisig_demo::instruction::ChangeThreshold { threshold: 2 }.data());
+        let wallet_meta = embedded.keys
+            .iter()
+            .find(|meta| meta.pubkey == multisig)
+            .unwrap();
+        assert!(wallet_meta.is_signer);
+
+        let (_, ixs) = member.propose_ixs(vec![], 0, None, false);
+        assert_eq!(ixs.len(), 1);
+    }
+
+    #[test]
+    fn fee_payer_pays_and_member_signs() {
+        let signer = Box::new(Keypair::new());
+        let member_pubkey = signer.pubkey();
+        let fee_payer = Box::new(Keypair::new());
+        let fee_payer_pubkey = fee_payer.pubkey();
+        let member = MultisigMember::new(
+            signer, Pubkey::new_unique(), MultisigWallet::default(), RpcClient::new_mock("succeeds"))
+            .with_fee_payer(fee_payer);
+        let ix = member.approve_ix(Pubkey::new_unique());
+        let config = TransactionConfig {
+            blockhash: Some(solana_program::hash::Hash::new_unique()),
+            ..Default::default()
+        };
+        let tx = member.build_transaction(&[ix], &config).unwrap();
+        assert_eq!(tx.message.account_keys[0], fee_payer_pubkey);
+        assert_eq!(tx.message.account_keys[1], member_pubkey);
+        assert!(tx.is_signed());
+    }
 }
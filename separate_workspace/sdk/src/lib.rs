@@ -1,5 +1,24 @@
+pub mod builders;
+pub mod client;
+pub mod decode;
+pub mod error;
+pub mod events;
 pub mod instructions;
+pub mod members;
+pub mod nonblocking;
 pub mod pda;
+pub mod simulation;
+pub mod status;
+pub mod transaction;
 
+pub use builders::*;
+pub use client::*;
+pub use decode::*;
+pub use error::*;
+pub use events::*;
 pub use instructions::*;
-pub use pda::*;
\ No newline at end of file
+pub use members::*;
+pub use pda::*;
+pub use simulation::*;
+pub use status::*;
+pub use transaction::*;
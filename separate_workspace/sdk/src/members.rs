@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+pub use multisig_demo::state::Member;
+use solana_program::pubkey::Pubkey;
+
+/// Split a `MEMBER[:WEIGHT]` spec into the member and its weight, which
+/// defaults to one. Only a numeric suffix is taken as the weight, so that
+/// members given as signer paths such as `usb://ledger` are left intact.
+pub fn split_member_spec(spec: &str) -> Result<(&str, u16)> {
+    let (member, weight) = match spec.rsplit_once(':') {
+        Some((member, weight)) if !weight.is_empty() && weight.chars().all(|c| c.is_ascii_digit()) => {
+            let weight = weight.parse()
+                .map_err(|err| anyhow!("invalid weight in member {}: {}", spec, err))?;
+            (member, weight)
+        }
+        _ => (spec, 1),
+    };
+    if weight == 0 {
+        return Err(anyhow!("member {} must have a weight greater than zero", member));
+    }
+    Ok((member, weight))
+}
+
+/// Parse a `PUBKEY[:WEIGHT]` member spec.
+pub fn parse_member(spec: &str) -> Result<Member> {
+    let (key, weight) = split_member_spec(spec)?;
+    let key: Pubkey = key.parse()
+        .map_err(|err| anyhow!("invalid pubkey in member {}: {}", spec, err))?;
+    Ok(Member { key, weight })
+}
+
+/// Sort `members` into the order that the program stores them in.
+pub fn sort_members(members: &mut [Member]) {
+    members.sort_by_key(|member| member.key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn member_specs() {
+        let key = Pubkey::new_unique();
+        assert_eq!(parse_member(&key.to_string()).unwrap(), Member { key, weight: 1 });
+        assert_eq!(parse_member(&format!("{}:3", key)).unwrap(), Member { key, weight: 3 });
+        assert!(parse_member(&format!("{}:0", key)).is_err());
+        assert!(parse_member(&format!("{}:65536", key)).is_err());
+        assert!(parse_member("not a pubkey:2").is_err());
+
+        assert_eq!(split_member_spec("usb://ledger").unwrap(), ("usb://ledger", 1));
+        assert_eq!(split_member_spec("usb://ledger?key=0:2").unwrap(), ("usb://ledger?key=0", 2));
+        assert_eq!(split_member_spec("~/member.json:").unwrap(), ("~/member.json:", 1));
+    }
+}
@@ -0,0 +1,801 @@
+//! Async counterparts of the SDK's RPC calls, over the nonblocking [RpcClient]
+//! or any other [MultisigClient], such as the `solana-program-test` `BanksClient`
+//! with the `banks` feature.
+//! Instructions are built by the same [MemberInstructions] as the blocking
+//! [crate::MultisigMember].
+
+use std::ops::Deref;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use anchor_client::anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+#[cfg(feature = "banks")]
+use solana_banks_client::{BanksClient, BanksClientError};
+#[cfg(feature = "banks")]
+use solana_banks_interface::TransactionConfirmationStatus;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::nonce_utils;
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_program::hash::Hash;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{self, uses_durable_nonce, Transaction, TransactionError};
+use solana_sdk::transport::TransportError;
+use multisig_demo::state::{MultisigTransaction, MultisigWallet};
+use crate::builders::MemberInstructions;
+use crate::error::SdkError;
+use crate::instructions::new_multisig_ix;
+use crate::members::Member;
+use crate::pda::find_multisig_transaction_address;
+use crate::simulation::{simulation_config, simulation_from_result, Simulation};
+use crate::transaction::{sign_transaction, Confirmation, SendProgress, TransactionConfig, POLL_INTERVAL};
+
+/// Async counterpart of [crate::MultisigClient].
+#[async_trait]
+pub trait MultisigClient: Send + Sync {
+    /// Fetch an account, or [None] if it doesn't exist.
+    async fn get_account(&self, address: &Pubkey) -> Result<Option<Account>>;
+
+    /// Fetch several accounts at once, in the order of `addresses`.
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>>;
+
+    async fn get_latest_blockhash(&self) -> Result<Hash>;
+
+    /// Whether transactions signed over `blockhash` can still land.
+    async fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool>;
+
+    /// Submit a fully signed transaction. Failures are returned as an [SdkError].
+    async fn send_transaction(&self, tx: &Transaction) -> Result<Signature>;
+
+    /// The result of a sent transaction once it reaches `commitment`, or [None]
+    /// if it hasn't yet.
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<transaction::Result<()>>>;
+
+    /// Simulate `tx` without verifying signatures, over the latest blockhash,
+    /// returning the state of `addresses` afterwards.
+    async fn simulate_transaction(
+        &self,
+        tx: &Transaction,
+        addresses: &[Pubkey],
+    ) -> Result<RpcSimulateTransactionResult>;
+}
+
+#[async_trait]
+impl MultisigClient for RpcClient {
+    async fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.get_account_with_commitment(address, self.commitment()).await?.value)
+    }
+
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        Ok(RpcClient::get_multiple_accounts(self, addresses).await?)
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(RpcClient::get_latest_blockhash(self).await?)
+    }
+
+    async fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool> {
+        Ok(RpcClient::is_blockhash_valid(self, blockhash, CommitmentConfig::processed()).await?)
+    }
+
+    async fn send_transaction(&self, tx: &Transaction) -> Result<Signature> {
+        Ok(RpcClient::send_transaction(self, tx)
+            .await
+            .map_err(|err| SdkError::from_client_error(err, tx))
+            ?)
+    }
+
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<transaction::Result<()>>> {
+        Ok(self.get_signature_status_with_commitment(signature, commitment).await?)
+    }
+
+    async fn simulate_transaction(
+        &self,
+        tx: &Transaction,
+        addresses: &[Pubkey],
+    ) -> Result<RpcSimulateTransactionResult> {
+        Ok(self.simulate_transaction_with_config(tx, simulation_config(addresses)).await?.value)
+    }
+}
+
+/// Runs the SDK in-process against a `solana-program-test` bank.
+/// Since its calls take `&mut self`, each one goes through a clone of the client,
+/// which shares the connection.
+#[cfg(feature = "banks")]
+#[async_trait]
+impl MultisigClient for BanksClient {
+    async fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(BanksClient::get_account(&mut self.clone(), *address).await?)
+    }
+
+    async fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let mut accounts = vec![];
+        for address in addresses {
+            accounts.push(MultisigClient::get_account(self, address).await?);
+        }
+        Ok(accounts)
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(BanksClient::get_latest_blockhash(&mut self.clone()).await?)
+    }
+
+    async fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool> {
+        // The bank only prices messages over blockhashes that are still valid.
+        let message = Message::new_with_blockhash(&[], Some(&Pubkey::default()), blockhash);
+        let fee = self.clone()
+            .get_fee_for_message_with_commitment_and_context(
+                tarpc::context::current(), CommitmentLevel::Processed, message)
+            .await?;
+        Ok(fee.is_some())
+    }
+
+    /// Unlike over RPC, this returns once the transaction is processed.
+    async fn send_transaction(&self, tx: &Transaction) -> Result<Signature> {
+        match self.clone().process_transaction_with_preflight(tx.clone()).await {
+            Ok(()) => Ok(tx.signatures[0]),
+            Err(BanksClientError::SimulationError { err, logs, .. }) =>
+                Err(SdkError::from_transaction_error(err, tx, logs).into()),
+            Err(BanksClientError::TransactionError(err)) =>
+                Err(SdkError::from_transaction_error(err, tx, vec![]).into()),
+            Err(err) => Err(SdkError::Client(ClientError::from(TransportError::from(err))).into()),
+        }
+    }
+
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<transaction::Result<()>>> {
+        let status = match self.clone().get_transaction_status(*signature).await? {
+            Some(status) => status,
+            None => return Ok(None),
+        };
+        let reached = match (commitment.commitment, &status.confirmation_status) {
+            (CommitmentLevel::Finalized, Some(TransactionConfirmationStatus::Finalized)) => true,
+            (CommitmentLevel::Finalized, _) => false,
+            (CommitmentLevel::Confirmed, Some(TransactionConfirmationStatus::Processed)) => false,
+            _ => true,
+        };
+        Ok(reached.then(|| status.err.map_or(Ok(()), Err)))
+    }
+
+    /// The banks server can't simulate on its own, but it runs an unchecked
+    /// simulation before processing a transaction, and skips processing if it
+    /// fails. Since `tx` isn't signed, it is never processed. If the simulation
+    /// succeeds, the server only reports the failed signature check, so neither
+    /// logs nor `addresses` are returned.
+    async fn simulate_transaction(
+        &self,
+        tx: &Transaction,
+        _addresses: &[Pubkey],
+    ) -> Result<RpcSimulateTransactionResult> {
+        let mut client = self.clone();
+        let mut tx = Transaction::new_unsigned(tx.message.clone());
+        tx.message.recent_blockhash = BanksClient::get_latest_blockhash(&mut client).await?;
+        let result = client
+            .process_transaction_with_preflight_and_commitment_and_context(
+                tarpc::context::current(), tx, CommitmentLevel::Processed)
+            .await?;
+        match (result.result, result.simulation_details) {
+            (Some(Err(err)), Some(details)) => Ok(RpcSimulateTransactionResult {
+                err: Some(err),
+                logs: Some(details.logs),
+                accounts: None,
+                units_consumed: Some(details.units_consumed),
+            }),
+            (Some(Err(TransactionError::SignatureFailure)), None) => Ok(RpcSimulateTransactionResult {
+                err: None,
+                logs: None,
+                accounts: None,
+                units_consumed: None,
+            }),
+            (result, _) => Err(anyhow!("unexpected result of simulating over banks: {:?}", result)),
+        }
+    }
+}
+
+/// A signer that can be shared across tasks.
+pub type SharedSigner = dyn Signer + Send + Sync;
+
+pub async fn fetch_transaction(addr: &Pubkey, client: &dyn MultisigClient) -> Result<MultisigTransaction> {
+    let act_data = fetch_account_data(addr, client).await?;
+    let tx = MultisigTransaction::try_deserialize(&mut act_data.as_slice())?;
+    Ok(tx)
+}
+
+pub async fn fetch_wallet(addr: &Pubkey, client: &dyn MultisigClient) -> Result<MultisigWallet> {
+    let act_data = fetch_account_data(addr, client).await?;
+    let wallet = MultisigWallet::try_deserialize(&mut act_data.as_slice())?;
+    Ok(wallet)
+}
+
+async fn fetch_account_data(addr: &Pubkey, client: &dyn MultisigClient) -> Result<Vec<u8>> {
+    let account = client.get_account(addr).await?
+        .ok_or_else(|| anyhow!("account {} not found", addr))?;
+    Ok(account.data)
+}
+
+/// See [crate::fetch_wallet_transactions].
+pub async fn fetch_wallet_transactions(
+    multisig_wallet: &Pubkey,
+    tx_nonce: u64,
+    client: &dyn MultisigClient,
+) -> Result<Vec<(Pubkey, MultisigTransaction)>> {
+    let addresses: Vec<Pubkey> = (0..tx_nonce)
+        .map(|nonce| find_multisig_transaction_address(multisig_wallet, nonce))
+        .collect();
+    let mut transactions = vec![];
+    // The RPC limits how many accounts can be fetched at once.
+    for chunk in addresses.chunks(100) {
+        let accounts = client.get_multiple_accounts(chunk).await?;
+        for (address, account) in chunk.iter().zip(accounts) {
+            if let Some(account) = account {
+                let data = MultisigTransaction::try_deserialize(
+                    &mut account.data.as_slice())?;
+                transactions.push((*address, data));
+            }
+        }
+    }
+    Ok(transactions)
+}
+
+/// Resolve the blockhash that [build_transaction] signs over: the given one,
+/// the one stored in the durable `nonce` account, or the latest one.
+pub async fn resolve_blockhash(
+    client: &dyn MultisigClient,
+    blockhash: Option<Hash>,
+    nonce: Option<Pubkey>,
+) -> Result<Hash> {
+    Ok(match (blockhash, nonce) {
+        (Some(blockhash), _) => blockhash,
+        (None, Some(nonce)) => {
+            let account = client.get_account(&nonce).await?
+                .ok_or_else(|| anyhow!("nonce account {} not found", nonce))?;
+            nonce_utils::data_from_account(&account)?.blockhash()
+        }
+        (None, None) => client.get_latest_blockhash().await?,
+    })
+}
+
+/// See [crate::build_transaction]. The returned future isn't [Send], since the
+/// config's signers aren't required to be [Sync]. [resolve_blockhash] followed by
+/// [sign_transaction] avoids holding them across an await.
+pub async fn build_transaction(
+    client: &dyn MultisigClient,
+    instructions: &[Instruction],
+    payer: &dyn Signer,
+    config: &TransactionConfig<'_>,
+) -> Result<Transaction> {
+    let blockhash = resolve_blockhash(
+        client, config.blockhash, config.nonce.map(|(nonce, _)| nonce)).await?;
+    sign_transaction(instructions, payer, config, blockhash)
+}
+
+/// See [crate::send_with_confirmation]. `sign` signs the transaction over the
+/// given blockhash, which [resolve_blockhash] resolves from the `blockhash` and
+/// `nonce` of the [TransactionConfig] it signs with, also when resending after expiry.
+pub async fn send_with_confirmation(
+    client: &dyn MultisigClient,
+    sign: &(dyn Fn(Hash) -> Result<Transaction> + Sync),
+    blockhash: Option<Hash>,
+    nonce: Option<Pubkey>,
+    confirmation: &Confirmation,
+    progress: &(dyn Fn(SendProgress) + Sync),
+) -> Result<Signature> {
+    let start = Instant::now();
+    let mut attempt = 0;
+    let mut tx = sign(resolve_blockhash(client, blockhash, nonce).await?)?;
+    loop {
+        let signature = client.send_transaction(&tx).await?;
+        progress(SendProgress::Sent { signature, attempt });
+        let commitment = match confirmation.commitment {
+            Some(commitment) => CommitmentConfig { commitment },
+            None => return Ok(signature),
+        };
+        if wait_for_confirmation(client, &tx, commitment, start, confirmation.timeout, progress).await? {
+            progress(SendProgress::Confirmed { signature });
+            return Ok(signature);
+        }
+        let expired = tx.message.recent_blockhash;
+        tx = sign(resolve_blockhash(client, blockhash, nonce).await?)?;
+        if tx.message.recent_blockhash == expired {
+            return Err(SdkError::BlockhashExpired { signature }.into());
+        }
+        attempt += 1;
+    }
+}
+
+/// Poll the status of the sent `tx` until it reaches `commitment`. Returns `false`
+/// if its blockhash expired without it landing, so that it can be resent.
+async fn wait_for_confirmation(
+    client: &dyn MultisigClient,
+    tx: &Transaction,
+    commitment: CommitmentConfig,
+    start: Instant,
+    timeout: Duration,
+    progress: &(dyn Fn(SendProgress) + Sync),
+) -> Result<bool> {
+    let signature = tx.signatures[0];
+    let mut expires = uses_durable_nonce(tx).is_none();
+    loop {
+        match client.get_signature_status(&signature, commitment).await? {
+            Some(Ok(())) => return Ok(true),
+            Some(Err(error)) => return Err(SdkError::from_transaction_error(error, tx, vec![]).into()),
+            None => {}
+        }
+        if start.elapsed() >= timeout {
+            return Err(SdkError::Timeout { signature }.into());
+        }
+        if expires && !client.is_blockhash_valid(&tx.message.recent_blockhash).await? {
+            // The transaction might have landed right before the blockhash expired,
+            // in which case it only remains to wait for the commitment level.
+            if client.get_signature_status(&signature, CommitmentConfig::processed())
+                .await?
+                .is_none() {
+                return Ok(false);
+            }
+            expires = false;
+        }
+        progress(SendProgress::Waiting { signature, elapsed: start.elapsed() });
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// See [crate::simulate_instructions].
+pub async fn simulate_instructions(
+    client: &dyn MultisigClient,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    addresses: &[Pubkey],
+) -> Result<Simulation> {
+    let pre = client.get_multiple_accounts(addresses).await?;
+    let tx = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+    let result = client.simulate_transaction(&tx, addresses).await?;
+    Ok(simulation_from_result(addresses, &pre, result))
+}
+
+/// See [crate::new_multisig_rpc].
+pub async fn new_multisig_rpc(
+    threshold: u16,
+    members: Vec<Member>,
+    client: &dyn MultisigClient,
+    payer: &SharedSigner,
+    base: Option<&SharedSigner>,
+    fee_payer: Option<&SharedSigner>,
+    confirmation: &Confirmation,
+) -> Result<Signature> {
+    let maybe_key = Keypair::new();
+    let base = base.unwrap_or(&maybe_key);
+    let ix = new_multisig_ix(threshold, members, &base.pubkey(), &payer.pubkey());
+    let sign = |blockhash| {
+        let config = TransactionConfig {
+            signers: vec![payer, base],
+            ..Default::default()
+        };
+        sign_transaction(std::slice::from_ref(&ix), fee_payer.unwrap_or(payer), &config, blockhash)
+    };
+    send_with_confirmation(client, &sign, None, None, confirmation, &|_| {}).await
+}
+
+/// Async counterpart of [crate::MultisigMember], for use on a tokio runtime.
+/// Signers must be [Send] and [Sync], so that its futures can be spawned.
+/// Calls go through a nonblocking [RpcClient] unless another [MultisigClient] is given.
+pub struct MultisigMember<C = RpcClient> {
+    member: Box<SharedSigner>,
+    /// Pays transaction fees instead of the member, if set.
+    fee_payer: Option<Box<SharedSigner>>,
+    /// How long to wait for sent transactions.
+    confirmation: Confirmation,
+    instructions: MemberInstructions,
+    client: C,
+}
+
+impl<C: MultisigClient> MultisigMember<C> {
+    /// This constructor pulls the multisig wallet metadata from on-chain.
+    pub async fn try_new(
+        member: Box<SharedSigner>,
+        multisig_address: Pubkey,
+        client: C,
+    ) -> Result<Self> {
+        let multisig_data = fetch_wallet(&multisig_address, &client).await?;
+        Ok(Self::new(member, multisig_address, multisig_data, client))
+    }
+
+    /// This constructor uses already fetched multisig wallet metadata.
+    pub fn new(
+        member: Box<SharedSigner>,
+        multisig_address: Pubkey,
+        multisig_data: MultisigWallet,
+        client: C,
+    ) -> Self {
+        Self {
+            instructions: MemberInstructions {
+                member: member.pubkey(),
+                multisig_address,
+                multisig_data,
+            },
+            member,
+            fee_payer: None,
+            confirmation: Confirmation::default(),
+            client,
+        }
+    }
+
+    /// See [crate::MultisigMember::with_fee_payer].
+    pub fn with_fee_payer(mut self, fee_payer: Box<SharedSigner>) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    /// See [crate::MultisigMember::with_confirmation].
+    pub fn with_confirmation(mut self, confirmation: Confirmation) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// Pull data from on-chain, mostly just in case the `tx_nonce` has
+    /// incremented.
+    pub async fn refresh_wallet(&mut self) -> Result<()> {
+        self.instructions.multisig_data = fetch_wallet(&self.multisig_address, &self.client).await?;
+        Ok(())
+    }
+
+    /// Fetch every [MultisigTransaction] proposed on this multisig wallet
+    /// that still exists on-chain, i.e. hasn't been closed.
+    pub async fn fetch_transactions(&self) -> Result<Vec<(Pubkey, MultisigTransaction)>> {
+        fetch_wallet_transactions(
+            &self.multisig_address, self.multisig_data.tx_nonce, &self.client).await
+    }
+
+    /// RPC call to propose a new [MultisigTransaction], optionally approving it as well.
+    /// `ttl` is zero for no expiry, or [None] for the wallet's default, which also
+    /// applies to the `propose_change_*` calls.
+    /// Returns the address of the proposal, and the transaction signature.
+    pub async fn propose(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let (proposal, ixs) = self.propose_ixs(instructions, vault_index, ttl, approve);
+        let signature = self.send_instructions(&ixs).await?;
+        // Keep the nonce current, so that consecutive proposals get the right address.
+        self.instructions.multisig_data.tx_nonce += 1;
+        Ok((proposal, signature))
+    }
+
+    /// RPC call to propose a change of the approval threshold.
+    pub async fn propose_change_threshold(
+        &mut self,
+        threshold: u16,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_threshold_ix(threshold);
+        self.propose(vec![ix], 0, ttl, approve).await
+    }
+
+    /// RPC call to propose a change of the timelock between a transaction
+    /// reaching the approval threshold and its execution.
+    pub async fn propose_change_min_delay(
+        &mut self,
+        min_delay: u32,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_min_delay_ix(min_delay);
+        self.propose(vec![ix], 0, ttl, approve).await
+    }
+
+    /// RPC call to propose a change of the default time-to-live of new transactions.
+    pub async fn propose_change_default_ttl(
+        &mut self,
+        default_ttl: u32,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_default_ttl_ix(default_ttl);
+        self.propose(vec![ix], 0, ttl, approve).await
+    }
+
+    /// RPC call to propose a change of the member set.
+    pub async fn propose_change_members(
+        &mut self,
+        members: Vec<Member>,
+        ttl: Option<u32>,
+        approve: bool,
+    ) -> Result<(Pubkey, Signature)> {
+        let ix = self.change_members_ix(members);
+        self.propose(vec![ix], 0, ttl, approve).await
+    }
+
+    /// RPC call to create a new transaction proposal under this object's
+    /// multisig wallet.
+    pub async fn new_transaction_rpc(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+    ) -> Result<Signature> {
+        let ix = self.new_transaction_ix(instructions, vault_index, ttl);
+        self.send_instructions(&[ix]).await
+    }
+
+    /// RPC call to approve a [MultisigTransaction].
+    pub async fn approve_rpc(&self, transaction: Pubkey) -> Result<Signature> {
+        let ix = self.approve_ix(transaction);
+        self.send_instructions(&[ix]).await
+    }
+
+    /// RPC call to cancel approval of a [MultisigTransaction].
+    pub async fn unapprove_rpc(&self, transaction: Pubkey) -> Result<Signature> {
+        let ix = self.unapprove_ix(transaction);
+        self.send_instructions(&[ix]).await
+    }
+
+    /// RPC call to reject a [MultisigTransaction].
+    pub async fn reject_rpc(&self, transaction: Pubkey) -> Result<Signature> {
+        let ix = self.reject_ix(transaction);
+        self.send_instructions(&[ix]).await
+    }
+
+    /// RPC call to execute a [MultisigTransaction].
+    pub async fn execute_rpc(&self, transaction: Pubkey) -> Result<Signature> {
+        let data = fetch_transaction(&transaction, &self.client).await?;
+        let ix = self.execute_ix(transaction, data);
+        self.send_instructions(&[ix]).await
+    }
+
+    /// See [crate::MultisigMember::simulate_execute].
+    pub async fn simulate_execute(&self, transaction: Pubkey) -> Result<Simulation> {
+        let data = fetch_transaction(&transaction, &self.client).await?;
+        // Judged by the local clock, which may be off from the cluster's by a few seconds.
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let (via_execute, instructions, addresses) =
+            self.simulate_execute_ixs(transaction, data, now);
+        let mut simulation = simulate_instructions(
+            &self.client, &instructions, &self.fee_payer().pubkey(), &addresses).await?;
+        simulation.via_execute = via_execute;
+        Ok(simulation)
+    }
+
+    /// RPC call to close a [MultisigTransaction] that can no longer be executed,
+    /// see [MultisigTransaction::is_closeable].
+    pub async fn close_transaction_rpc(&self, transaction: Pubkey) -> Result<Signature> {
+        let data = fetch_transaction(&transaction, &self.client).await?;
+        let ix = self.close_transaction_ix(transaction, data.proposer);
+        self.send_instructions(&[ix]).await
+    }
+
+    /// RPC call with arbitrary instructions, signed by the member,
+    /// and paid for by the fee payer. Waits for the transaction as configured
+    /// with [MultisigMember::with_confirmation].
+    pub async fn send_instructions(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let sign = |blockhash| self.sign_transaction(instructions, &TransactionConfig::default(), blockhash);
+        send_with_confirmation(&self.client, &sign, None, None, &self.confirmation, &|_| {}).await
+    }
+
+    /// Assemble a transaction signed by this member and paid for by the fee payer,
+    /// without sending it. See [build_transaction] about the returned future.
+    pub async fn build_transaction(
+        &self,
+        instructions: &[Instruction],
+        config: &TransactionConfig<'_>,
+    ) -> Result<Transaction> {
+        let blockhash = resolve_blockhash(
+            &self.client, config.blockhash, config.nonce.map(|(nonce, _)| nonce)).await?;
+        self.sign_transaction(instructions, config, blockhash)
+    }
+
+    /// Assemble a transaction like [MultisigMember::build_transaction],
+    /// signing over `blockhash`.
+    pub fn sign_transaction(
+        &self,
+        instructions: &[Instruction],
+        config: &TransactionConfig,
+        blockhash: Hash,
+    ) -> Result<Transaction> {
+        let mut config = config.clone();
+        config.signers.push(self.signer());
+        sign_transaction(instructions, self.fee_payer(), &config, blockhash)
+    }
+
+    /// The signer acting as this multisig member.
+    pub fn signer(&self) -> &SharedSigner {
+        self.member.as_ref()
+    }
+
+    /// The signer paying transaction fees, which defaults to the member.
+    pub fn fee_payer(&self) -> &SharedSigner {
+        self.fee_payer.as_deref().unwrap_or(self.member.as_ref())
+    }
+
+    /// The client used by this member.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    /// RPC call with a compound transaction for convenience,
+    /// issuing both the [CreateTransaction] and [Approve] instructions.
+    pub async fn create_and_approve_tx(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault_index: u8,
+        ttl: Option<u32>,
+    ) -> Result<Signature> {
+        let ix = self.new_transaction_ix(instructions, vault_index, ttl);
+        let transaction = self.next_transaction_pubkey();
+        let ix2 = self.approve_ix(transaction);
+        self.send_instructions(&[ix, ix2]).await
+    }
+
+    /// RPC call with a compound transaction for convenience,
+    /// issuing both the [Approve] and [Execute] instructions.
+    pub async fn approve_and_execute_tx(&self, transaction: Pubkey) -> Result<Signature> {
+        let ix = self.approve_ix(transaction);
+        let data = fetch_transaction(&transaction, &self.client).await?;
+        let ix2 = self.execute_ix(transaction, data);
+        self.send_instructions(&[ix, ix2]).await
+    }
+}
+
+impl<C> Deref for MultisigMember<C> {
+    type Target = MemberInstructions;
+
+    fn deref(&self) -> &MemberInstructions {
+        &self.instructions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn async_member_matches_blocking_member() {
+        let signer = Keypair::new();
+        let multisig = Pubkey::new_unique();
+        let blocking = crate::MultisigMember::new(
+            Box::new(Keypair::from_bytes(&signer.to_bytes()).unwrap()), multisig,
+            MultisigWallet::default(), solana_client::rpc_client::RpcClient::new_mock("succeeds"));
+        let member = MultisigMember::new(
+            Box::new(signer), multisig, MultisigWallet::default(),
+            RpcClient::new_mock("succeeds".to_string()));
+        let transaction = Pubkey::new_unique();
+        assert_eq!(member.approve_ix(transaction), blocking.approve_ix(transaction));
+        assert_eq!(member.propose_ixs(vec![], 1, Some(60), true),
+            blocking.propose_ixs(vec![], 1, Some(60), true));
+
+        // Futures of the member can be spawned on a multi-threaded runtime.
+        assert_send(&member.approve_rpc(transaction));
+
+        let blockhash = Hash::new_unique();
+        let config = TransactionConfig {
+            blockhash: Some(blockhash),
+            ..Default::default()
+        };
+        let tx = member.build_transaction(&[member.approve_ix(transaction)], &config).await.unwrap();
+        assert_eq!(tx.message.recent_blockhash, blockhash);
+        assert!(tx.is_signed());
+
+        // Sending signs over the configured blockhash rather than the latest one.
+        let signed_over = std::sync::Mutex::new(vec![]);
+        let sign = |blockhash| {
+            signed_over.lock().unwrap().push(blockhash);
+            member.sign_transaction(&[member.approve_ix(transaction)], &TransactionConfig::default(), blockhash)
+        };
+        send_with_confirmation(member.client(), &sign, Some(blockhash), None, &Confirmation::none(), &|_| {})
+            .await
+            .unwrap();
+        assert_eq!(*signed_over.lock().unwrap(), vec![blockhash]);
+    }
+
+    /// A program-owned account holding `data`, as created by the program.
+    fn program_account<T: anchor_client::anchor_lang::AccountSerialize>(data: &T, space: usize) -> Account {
+        let mut buffer = vec![0; space];
+        data.try_serialize(&mut buffer.as_mut_slice()).unwrap();
+        Account {
+            lamports: solana_sdk::rent::Rent::default().minimum_balance(space),
+            data: buffer,
+            owner: multisig_demo::ID,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn member_flow_over_banks_client() {
+        use solana_program_test::{processor, ProgramTest};
+        use solana_sdk::native_token::LAMPORTS_PER_SOL;
+        use solana_sdk::system_instruction;
+
+        // Natively loaded programs can't create accounts through CPI in this version of
+        // `solana-program-test`, so the wallet and its proposals are added up front.
+        let mut program = ProgramTest::new(
+            "multisig_demo", multisig_demo::ID, processor!(multisig_demo::entry));
+        let signer = Keypair::new();
+        let base = Pubkey::new_unique();
+        let (multisig, bump) = Pubkey::find_program_address(
+            &[b"MultisigWallet".as_ref(), base.as_ref()], &multisig_demo::ID);
+        let wallet = MultisigWallet {
+            base,
+            members: vec![Member { key: signer.pubkey(), weight: 1 }],
+            threshold: 1,
+            tx_nonce: 2,
+            bump,
+            ..Default::default()
+        };
+        program.add_account(multisig, program_account(&wallet, MultisigWallet::space(1)));
+        let vault = crate::find_vault_address(&multisig, 0);
+        program.add_account(vault, Account {
+            lamports: 2 * LAMPORTS_PER_SOL,
+            ..Default::default()
+        });
+        program.add_account(signer.pubkey(), Account {
+            lamports: LAMPORTS_PER_SOL,
+            ..Default::default()
+        });
+        let recipient = Pubkey::new_unique();
+        let proposals: Vec<Pubkey> = [3, 1].iter().enumerate().map(|(nonce, sol)| {
+            let instructions = vec![
+                system_instruction::transfer(&vault, &recipient, sol * LAMPORTS_PER_SOL).into()];
+            let space = MultisigTransaction::space(instructions.clone(), 1);
+            let proposal = MultisigTransaction {
+                instructions,
+                multisig_wallet: multisig,
+                approved: vec![None],
+                rejected: vec![None],
+                proposer: signer.pubkey(),
+                ..Default::default()
+            };
+            let address = find_multisig_transaction_address(&multisig, nonce as u64);
+            program.add_account(address, program_account(&proposal, space));
+            address
+        }).collect();
+
+        let (client, _, _) = program.start().await;
+        let member = MultisigMember::try_new(Box::new(signer), multisig, client)
+            .await
+            .unwrap()
+            .with_confirmation(Confirmation::processed());
+        assert_eq!(member.fetch_transactions().await.unwrap().len(), 2);
+
+        // Simulating a proposal that can't succeed reports the failure without processing it.
+        member.approve_rpc(proposals[0]).await.unwrap();
+        let simulation = member.simulate_execute(proposals[0]).await.unwrap();
+        assert!(simulation.via_execute);
+        assert!(simulation.err.is_some());
+        assert!(!simulation.logs.is_empty());
+        let err = member.execute_rpc(proposals[0]).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<SdkError>(), Some(SdkError::Transaction { .. })));
+
+        member.approve_rpc(proposals[1]).await.unwrap();
+        let simulation = member.simulate_execute(proposals[1]).await.unwrap();
+        assert!(simulation.via_execute);
+        assert_eq!(simulation.err, None);
+        member.execute_rpc(proposals[1]).await.unwrap();
+        let account = MultisigClient::get_account(member.client(), &recipient).await.unwrap().unwrap();
+        assert_eq!(account.lamports, LAMPORTS_PER_SOL);
+
+        let err = member.unapprove_rpc(proposals[1]).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<SdkError>().unwrap().multisig_error(),
+            Some(multisig_demo::error::MultisigError::AlreadyExecuted));
+    }
+}
@@ -25,3 +25,17 @@ pub fn find_multisig_transaction_address(
         &multisig_demo::ID,
     ).0
 }
+
+pub fn find_vault_address(
+    multisig_wallet: &Pubkey,
+    index: u8,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"Vault".as_ref(),
+            multisig_wallet.as_ref(),
+            &[index],
+        ],
+        &multisig_demo::ID,
+    ).0
+}
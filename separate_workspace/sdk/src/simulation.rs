@@ -0,0 +1,187 @@
+use anyhow::Result;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use crate::client::MultisigClient;
+
+/// Outcome of simulating a transaction, see [crate::MultisigMember::simulate_execute].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simulation {
+    /// Whether the simulation went through the [multisig_demo::instruction::Execute]
+    /// instruction. Otherwise the proposed instructions were simulated on their own,
+    /// because the proposal can't be executed yet.
+    pub via_execute: bool,
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Balances of the writable accounts before and after the transaction.
+    /// Empty if the simulation failed.
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub address: Pubkey,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    /// Set if the account is an SPL Token account.
+    pub pre_token: Option<TokenBalance>,
+    pub post_token: Option<TokenBalance>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl BalanceChange {
+    pub fn lamports_delta(&self) -> i128 {
+        self.post_lamports as i128 - self.pre_lamports as i128
+    }
+
+    /// Change in the token amount, if the account holds tokens before or after.
+    pub fn token_delta(&self) -> Option<(Pubkey, i128)> {
+        let mint = self.post_token.or(self.pre_token)?.mint;
+        let amount = |balance: Option<TokenBalance>| balance.map_or(0, |b| b.amount as i128);
+        Some((mint, amount(self.post_token) - amount(self.pre_token)))
+    }
+}
+
+/// Simulate `instructions` paid for by `payer`, without verifying signatures,
+/// and report the balances of `addresses` before and after.
+/// Since nothing is signed, the cluster's latest blockhash is used.
+pub fn simulate_instructions(
+    client: &dyn MultisigClient,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    addresses: &[Pubkey],
+) -> Result<Simulation> {
+    let pre = client.get_multiple_accounts(addresses)?;
+    let tx = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+    let result = client.simulate_transaction(&tx, addresses)?;
+    Ok(simulation_from_result(addresses, &pre, result))
+}
+
+/// Simulate without signatures, returning the state of `addresses`.
+pub(crate) fn simulation_config(addresses: &[Pubkey]) -> RpcSimulateTransactionConfig {
+    RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: addresses.iter().map(Pubkey::to_string).collect(),
+        }),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn simulation_from_result(
+    addresses: &[Pubkey],
+    pre: &[Option<Account>],
+    result: RpcSimulateTransactionResult,
+) -> Simulation {
+    let balance_changes = match result.accounts {
+        Some(post) => {
+            let post: Vec<Option<Account>> = post
+                .iter()
+                .map(|account| account.as_ref().and_then(|account| account.decode()))
+                .collect();
+            balance_changes(addresses, pre, &post)
+        }
+        None => vec![],
+    };
+    Simulation {
+        via_execute: false,
+        err: result.err,
+        logs: result.logs.unwrap_or_default(),
+        units_consumed: result.units_consumed,
+        balance_changes,
+    }
+}
+
+/// Pair up the `pre` and `post` states of `addresses`. Missing accounts have no balance.
+pub fn balance_changes(
+    addresses: &[Pubkey],
+    pre: &[Option<Account>],
+    post: &[Option<Account>],
+) -> Vec<BalanceChange> {
+    addresses
+        .iter()
+        .zip(pre.iter().zip(post))
+        .map(|(address, (pre, post))| BalanceChange {
+            address: *address,
+            pre_lamports: pre.as_ref().map_or(0, |account| account.lamports),
+            post_lamports: post.as_ref().map_or(0, |account| account.lamports),
+            pre_token: pre.as_ref().and_then(token_balance),
+            post_token: post.as_ref().and_then(token_balance),
+        })
+        .collect()
+}
+
+fn token_balance(account: &Account) -> Option<TokenBalance> {
+    if account.owner != spl_token::ID {
+        return None;
+    }
+    let token = spl_token::state::Account::unpack(&account.data).ok()?;
+    Some(TokenBalance {
+        mint: token.mint,
+        amount: token.amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(mint: Pubkey, amount: u64, lamports: u64) -> Account {
+        let token = spl_token::state::Account {
+            mint,
+            owner: Pubkey::new_unique(),
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        token.pack_into_slice(&mut data);
+        Account {
+            lamports,
+            data,
+            owner: spl_token::ID,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn balance_changes_of_lamports_and_tokens() {
+        let vault = Pubkey::new_unique();
+        let token = Pubkey::new_unique();
+        let created = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let changes = balance_changes(
+            &[vault, token, created],
+            &[
+                Some(Account { lamports: 1_000, ..Default::default() }),
+                Some(token_account(mint, 50, 10)),
+                None,
+            ],
+            &[
+                Some(Account { lamports: 400, ..Default::default() }),
+                Some(token_account(mint, 20, 10)),
+                Some(Account { lamports: 600, ..Default::default() }),
+            ],
+        );
+        assert_eq!(changes[0].lamports_delta(), -600);
+        assert_eq!(changes[0].token_delta(), None);
+        assert_eq!(changes[1].lamports_delta(), 0);
+        assert_eq!(changes[1].token_delta(), Some((mint, -30)));
+        assert_eq!(changes[2].pre_lamports, 0);
+        assert_eq!(changes[2].lamports_delta(), 600);
+    }
+}
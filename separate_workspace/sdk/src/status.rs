@@ -0,0 +1,138 @@
+use std::fmt;
+use multisig_demo::state::{MultisigTransaction, MultisigWallet};
+use serde::Serialize;
+
+/// Summary of where a [MultisigTransaction] stands in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum TransactionStatus {
+    /// Still collecting approvals, until their combined weight reaches the threshold.
+    Pending {
+        #[serde(rename = "approvedWeight")]
+        approved_weight: u64,
+        threshold: u16,
+    },
+    /// Reached the threshold, but the wallet's timelock hasn't elapsed yet.
+    Timelocked {
+        #[serde(rename = "executableAt")]
+        executable_at: i64,
+    },
+    /// Can be executed right away.
+    Executable,
+    Executed {
+        #[serde(rename = "executedAt")]
+        executed_at: i64,
+    },
+    Cancelled {
+        #[serde(rename = "cancelledAt")]
+        cancelled_at: i64,
+    },
+    Expired {
+        #[serde(rename = "expiredAt")]
+        expired_at: i64,
+    },
+    /// Created under a previous wallet configuration, so it can never be executed.
+    Stale,
+}
+
+impl TransactionStatus {
+    /// Compute the status of `transaction` under `multisig_wallet` at unix timestamp `now`.
+    pub fn of(
+        transaction: &MultisigTransaction,
+        multisig_wallet: &MultisigWallet,
+        now: i64,
+    ) -> Self {
+        if let Some(executed_at) = transaction.executed_at {
+            return TransactionStatus::Executed { executed_at };
+        }
+        if let Some(cancelled_at) = transaction.cancelled_at {
+            return TransactionStatus::Cancelled { cancelled_at };
+        }
+        if transaction.config_seqno != multisig_wallet.config_seqno {
+            return TransactionStatus::Stale;
+        }
+        if let Some(expired_at) = transaction.expires_at.filter(|_| transaction.is_expired(now)) {
+            return TransactionStatus::Expired { expired_at };
+        }
+        let approved_weight = transaction.approved_weight(multisig_wallet);
+        if approved_weight < multisig_wallet.threshold as u64 {
+            return TransactionStatus::Pending {
+                approved_weight,
+                threshold: multisig_wallet.threshold,
+            };
+        }
+        match transaction.executable_at(multisig_wallet) {
+            Some(executable_at) if executable_at > now && multisig_wallet.min_delay > 0 => {
+                TransactionStatus::Timelocked { executable_at }
+            }
+            _ => TransactionStatus::Executable,
+        }
+    }
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionStatus::Pending { approved_weight, threshold } => {
+                write!(f, "pending ({}/{} approval weight)", approved_weight, threshold)
+            }
+            TransactionStatus::Timelocked { executable_at } => {
+                write!(f, "timelocked until {}", executable_at)
+            }
+            TransactionStatus::Executable => write!(f, "executable"),
+            TransactionStatus::Executed { executed_at } => {
+                write!(f, "executed at {}", executed_at)
+            }
+            TransactionStatus::Cancelled { cancelled_at } => {
+                write!(f, "cancelled at {}", cancelled_at)
+            }
+            TransactionStatus::Expired { expired_at } => {
+                write!(f, "expired at {}", expired_at)
+            }
+            TransactionStatus::Stale => write!(f, "stale (previous configuration)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multisig_demo::state::Member;
+    use super::*;
+
+    #[test]
+    fn transaction_status() {
+        let wallet = MultisigWallet {
+            members: [2, 1, 1].into_iter()
+                .map(|weight| Member { key: Default::default(), weight })
+                .collect(),
+            threshold: 3,
+            min_delay: 100,
+            config_seqno: 1,
+            ..Default::default()
+        };
+        let mut tx = MultisigTransaction {
+            approved: vec![None, Some(10), None],
+            config_seqno: 1,
+            expires_at: Some(1_000),
+            ..Default::default()
+        };
+        assert_eq!(TransactionStatus::of(&tx, &wallet, 50),
+            TransactionStatus::Pending { approved_weight: 1, threshold: 3 });
+
+        tx.approved[0] = Some(20);
+        tx.threshold_reached_at = Some(20);
+        assert_eq!(TransactionStatus::of(&tx, &wallet, 50),
+            TransactionStatus::Timelocked { executable_at: 120 });
+        assert_eq!(TransactionStatus::of(&tx, &wallet, 120),
+            TransactionStatus::Executable);
+        assert_eq!(TransactionStatus::of(&tx, &wallet, 1_001),
+            TransactionStatus::Expired { expired_at: 1_000 });
+
+        tx.config_seqno = 0;
+        assert_eq!(TransactionStatus::of(&tx, &wallet, 120), TransactionStatus::Stale);
+
+        tx.executed_at = Some(130);
+        assert_eq!(TransactionStatus::of(&tx, &wallet, 1_001),
+            TransactionStatus::Executed { executed_at: 130 });
+    }
+}
@@ -0,0 +1,257 @@
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use anyhow::{anyhow, Result};
+use solana_client::nonce_utils;
+use solana_program::hash::Hash;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::message::Message;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{uses_durable_nonce, Transaction};
+use crate::client::MultisigClient;
+use crate::error::SdkError;
+
+/// Controls how a transaction is assembled, mostly to support signing offline.
+#[derive(Clone, Default)]
+pub struct TransactionConfig<'a> {
+    /// Sign over this blockhash instead of fetching one from the cluster.
+    /// With a durable nonce, this is the blockhash stored in the nonce account.
+    pub blockhash: Option<Hash>,
+    /// Durable nonce account and its authority. The nonce is advanced by the
+    /// transaction's first instruction, which keeps the transaction valid until
+    /// the nonce is advanced by something else.
+    pub nonce: Option<(Pubkey, &'a dyn Signer)>,
+    /// Signers in addition to the fee payer and the nonce authority,
+    /// e.g. presigners holding signatures made on another machine.
+    pub signers: Vec<&'a dyn Signer>,
+}
+
+/// Assemble a transaction paid for by `payer`, and sign it with every signer
+/// that it requires. Signers that can't sign, such as a [solana_sdk::signer::null_signer::NullSigner]
+/// in sign-only mode, leave a default signature, so check [Transaction::is_signed] before sending.
+pub fn build_transaction(
+    client: &dyn MultisigClient,
+    instructions: &[Instruction],
+    payer: &dyn Signer,
+    config: &TransactionConfig,
+) -> Result<Transaction> {
+    let blockhash = match (config.blockhash, config.nonce) {
+        (Some(blockhash), _) => blockhash,
+        (None, Some((nonce, _))) => {
+            let account = client.get_account(&nonce)?
+                .ok_or_else(|| anyhow!("nonce account {} not found", nonce))?;
+            nonce_utils::data_from_account(&account)?.blockhash()
+        }
+        (None, None) => client.get_latest_blockhash()?,
+    };
+    sign_transaction(instructions, payer, config, blockhash)
+}
+
+/// Assemble a transaction like [build_transaction], signing over `blockhash`
+/// instead of resolving it from the config or the cluster.
+pub fn sign_transaction(
+    instructions: &[Instruction],
+    payer: &dyn Signer,
+    config: &TransactionConfig,
+    blockhash: Hash,
+) -> Result<Transaction> {
+    let mut ixs = vec![];
+    if let Some((nonce, authority)) = config.nonce {
+        ixs.push(system_instruction::advance_nonce_account(&nonce, &authority.pubkey()));
+    }
+    ixs.extend_from_slice(instructions);
+    let mut tx = Transaction::new_unsigned(Message::new(&ixs, Some(&payer.pubkey())));
+    let required = &tx.message.account_keys[..tx.message.header.num_required_signatures as usize];
+    let mut signers: Vec<&dyn Signer> = vec![];
+    let candidates = std::iter::once(payer)
+        .chain(config.nonce.map(|(_, authority)| authority))
+        .chain(config.signers.iter().copied());
+    for signer in candidates {
+        let pubkey = signer.pubkey();
+        if required.contains(&pubkey) && !signers.iter().any(|s| s.pubkey() == pubkey) {
+            signers.push(signer);
+        }
+    }
+    tx.try_partial_sign(&signers, blockhash)?;
+    Ok(tx)
+}
+
+/// How long to wait for a transaction after sending it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Confirmation {
+    /// Commitment level to wait for, or [None] to return as soon as the
+    /// transaction is submitted.
+    pub commitment: Option<CommitmentLevel>,
+    /// Give up waiting after this long, with [SdkError::Timeout].
+    pub timeout: Duration,
+}
+
+impl Confirmation {
+    /// Don't wait, the transaction may still be dropped.
+    pub fn none() -> Self {
+        Confirmation { commitment: None, ..Default::default() }
+    }
+
+    pub fn processed() -> Self {
+        Confirmation { commitment: Some(CommitmentLevel::Processed), ..Default::default() }
+    }
+
+    pub fn confirmed() -> Self {
+        Confirmation::default()
+    }
+
+    pub fn finalized() -> Self {
+        Confirmation { commitment: Some(CommitmentLevel::Finalized), ..Default::default() }
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Confirmation { timeout, ..self }
+    }
+}
+
+impl Default for Confirmation {
+    fn default() -> Self {
+        Confirmation {
+            commitment: Some(CommitmentLevel::Confirmed),
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Progress reported by [send_with_confirmation].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SendProgress {
+    /// The transaction was submitted. `attempt` counts the resends after blockhash expiry.
+    Sent { signature: Signature, attempt: u32 },
+    /// Still waiting for the transaction to reach the commitment level.
+    Waiting { signature: Signature, elapsed: Duration },
+    /// The transaction reached the commitment level.
+    Confirmed { signature: Signature },
+}
+
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Send the transaction made by `build`, and wait for it according to `confirmation`.
+/// If its blockhash expires before it lands, `build` is called again to sign over
+/// a newer blockhash and the transaction is resent, until the timeout.
+/// A transaction that can't be rebuilt with a newer blockhash, e.g. one signed offline,
+/// fails with [SdkError::BlockhashExpired] instead. Durable nonce transactions don't expire.
+pub fn send_with_confirmation(
+    client: &dyn MultisigClient,
+    build: &dyn Fn() -> Result<Transaction>,
+    confirmation: &Confirmation,
+    progress: &dyn Fn(SendProgress),
+) -> Result<Signature> {
+    let start = Instant::now();
+    let mut attempt = 0;
+    let mut tx = build()?;
+    loop {
+        let signature = client.send_transaction(&tx)?;
+        progress(SendProgress::Sent { signature, attempt });
+        let commitment = match confirmation.commitment {
+            Some(commitment) => CommitmentConfig { commitment },
+            None => return Ok(signature),
+        };
+        if wait_for_confirmation(client, &tx, commitment, start, confirmation.timeout, progress)? {
+            progress(SendProgress::Confirmed { signature });
+            return Ok(signature);
+        }
+        let expired = tx.message.recent_blockhash;
+        tx = build()?;
+        if tx.message.recent_blockhash == expired {
+            return Err(SdkError::BlockhashExpired { signature }.into());
+        }
+        attempt += 1;
+    }
+}
+
+/// Poll the status of the sent `tx` until it reaches `commitment`. Returns `false`
+/// if its blockhash expired without it landing, so that it can be resent.
+fn wait_for_confirmation(
+    client: &dyn MultisigClient,
+    tx: &Transaction,
+    commitment: CommitmentConfig,
+    start: Instant,
+    timeout: Duration,
+    progress: &dyn Fn(SendProgress),
+) -> Result<bool> {
+    let signature = tx.signatures[0];
+    let mut expires = uses_durable_nonce(tx).is_none();
+    loop {
+        match client.get_signature_status(&signature, commitment)? {
+            Some(Ok(())) => return Ok(true),
+            Some(Err(error)) => return Err(SdkError::from_transaction_error(error, tx, vec![]).into()),
+            None => {}
+        }
+        if start.elapsed() >= timeout {
+            return Err(SdkError::Timeout { signature }.into());
+        }
+        if expires && !client.is_blockhash_valid(&tx.message.recent_blockhash)? {
+            // The transaction might have landed right before the blockhash expired,
+            // in which case it only remains to wait for the commitment level.
+            if client.get_signature_status(&signature, CommitmentConfig::processed())?
+                .is_none() {
+                return Ok(false);
+            }
+            expires = false;
+        }
+        progress(SendProgress::Waiting { signature, elapsed: start.elapsed() });
+        sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_client::RpcClient;
+    use solana_sdk::signature::{Keypair, NullSigner};
+
+    #[test]
+    fn build_offline_nonce_transaction() {
+        let client = RpcClient::new_mock("succeeds");
+        let payer = Keypair::new();
+        let nonce_authority = NullSigner::new(&Pubkey::new_unique());
+        let nonce = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let memo = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+        let unrelated = NullSigner::new(&Pubkey::new_unique());
+        let config = TransactionConfig {
+            blockhash: Some(blockhash),
+            nonce: Some((nonce, &nonce_authority)),
+            // Not required by the transaction, so it is ignored.
+            signers: vec![&unrelated],
+        };
+        let tx = build_transaction(&client, &[memo], &payer, &config).unwrap();
+        assert_eq!(tx.message.recent_blockhash, blockhash);
+        assert_eq!(tx.message.instructions.len(), 2);
+        assert_eq!(tx.message.account_keys[tx.message.instructions[0].program_id_index as usize],
+            solana_sdk::system_program::ID);
+        // The payer signed, but the nonce authority still has to sign elsewhere.
+        assert_eq!(tx.signatures.len(), 2);
+        assert!(tx.signatures[0].verify(payer.pubkey().as_ref(), &tx.message_data()));
+        assert_eq!(tx.signatures[1], Signature::default());
+        assert!(!tx.is_signed());
+    }
+
+    #[test]
+    fn wait_for_confirmation_status() {
+        let payer = Keypair::new();
+        let memo = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+        let tx = Transaction::new_signed_with_payer(
+            &[memo], Some(&payer.pubkey()), &[&payer], Hash::new_unique());
+        let timeout = Duration::from_secs(1);
+
+        let client = RpcClient::new_mock("succeeds");
+        let landed = wait_for_confirmation(&client, &tx, CommitmentConfig::confirmed(),
+            Instant::now(), timeout, &|_| {}).unwrap();
+        assert!(landed);
+
+        let client = RpcClient::new_mock("instruction_error");
+        let err = wait_for_confirmation(&client, &tx, CommitmentConfig::confirmed(),
+            Instant::now(), timeout, &|_| {}).unwrap_err();
+        assert!(matches!(err.downcast_ref::<SdkError>(), Some(SdkError::Transaction { .. })));
+    }
+}
@@ -3,7 +3,7 @@ import { Program } from "@project-serum/anchor";
 import * as acts from "./accounts";
 import {assert}  from "chai";
 import {createMemoInstruction} from "@solana/spl-memo";
-import {newMultisigRpc, findMultisigTransactionAddress, findMultisigWalletAddress, MultisigMember } from "@demo/multisig";
+import {newMultisigRpc, findMultisigTransactionAddress, findMultisigWalletAddress, findVaultAddress, MultisigMember, sortMembers } from "@demo/multisig";
 import {MultisigDemo} from "@demo/multisig/dist/multisig_demo";
 
 
@@ -35,6 +35,23 @@ describe("multisig-demo", () => {
     program.programId
   );
 
+  // Proposed before the member set changes, and should be unusable afterwards.
+  let staleTransaction: anchor.web3.PublicKey;
+
+  /// Transfers lamports from User1 to a multisig vault.
+  async function fundVault(vault: anchor.web3.PublicKey, lamports: number) {
+    const conn = anchor.getProvider().connection;
+    const tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({
+        fromPubkey: acts.testUser1,
+        toPubkey: vault,
+        lamports,
+      })
+    );
+    await anchor.web3.sendAndConfirmTransaction(conn, tx,
+      [acts.testUser1Keypair], {commitment: "processed"});
+  }
+
   it("State is properly saturated by the Test.toml", async () => {
     let conn = anchor.getProvider().connection;
 
@@ -88,6 +105,24 @@ describe("multisig-demo", () => {
     assert.isNotNull(err);
   });
 
+  it("Cannot initialize multisig with duplicate members", async () => {
+    // Non-adjacent duplicates are also caught, since members are sorted first.
+    let err = null;
+    try {
+      const signature = await newMultisigRpc(
+        baseKeypair,
+        acts.testUser1Keypair,
+        threshold,
+        [acts.testUser1, acts.testUser2, acts.testUser1],
+        program,
+        {commitment: "processed"}
+      )
+    } catch (e) {
+      err = e;
+    }
+    assert.isNotNull(err);
+  });
+
   it("Initialize 2 of 3 multisig of members [User1, User2, User3])", async () => {
     try {
       const signature = await newMultisigRpc(
@@ -234,6 +269,57 @@ describe("multisig-demo", () => {
     }
   });
 
+  it("Vault holds and sends SOL through an executed transaction", async () => {
+    const conn = anchor.getProvider().connection;
+    const vault = findVaultAddress(multisigWallet, 0, program.programId);
+    const amount = 100_000_000;
+    await fundVault(vault, amount);
+    assert((await conn.getBalance(vault, "processed")) === amount);
+
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    const msigMember2 = await MultisigMember.newFromAddress(
+      acts.testUser2Keypair, multisigWallet, program, "processed");
+    let msigTransaction = msigMember1.nextTransactionAddress();
+    const ix = anchor.web3.SystemProgram.transfer({
+      fromPubkey: vault,
+      toPubkey: acts.testUser4,
+      lamports: amount,
+    });
+    try {
+      await msigMember1.newTransactionAndApproveRpc([ix],
+        {commitment: "processed"}, 0);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await msigMember2.approveAndExecuteRpc(msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    assert((await conn.getBalance(vault, "processed")) === 0);
+    assert((await conn.getBalance(acts.testUser4, "processed")) === STARTING_BALANCE + amount);
+  });
+
+  it("User1 and User2 approve a transaction that will go stale", async () => {
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    const msigMember2 = await MultisigMember.newFromAddress(
+      acts.testUser2Keypair, multisigWallet, program, "processed");
+    staleTransaction = msigMember1.nextTransactionAddress();
+    const ix = createMemoInstruction("proposed under the old member set", [multisigWallet]);
+    try {
+      await msigMember1.newTransactionAndApproveRpc([ix],
+        {commitment: "processed"});
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await msigMember2.approveRpc(staleTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+  });
+
   it("Change Members to let User4 in", async () => {
     const msigMember1 = await MultisigMember.newFromAddress(
       acts.testUser1Keypair, multisigWallet, program);
@@ -259,16 +345,80 @@ describe("multisig-demo", () => {
     await new Promise((resolve) => setTimeout(resolve, 2000));
     await msigMember1.refreshWallet("processed");
     let wallet = await program.account.multisigWallet.fetch(multisigWallet, "processed");
-    assert(wallet.memberSetSeqno === 1);
+    assert(wallet.configSeqno === 1);
+    // Members are stored in sorted order
+    assert.deepEqual(
+      wallet.members.map((member) => member.key.toBase58()),
+      sortMembers(wallet.members).map((member) => member.key.toBase58()),
+    );
     let user4Included = false;
     wallet.members.forEach((member) => {
-      if (member.equals(acts.testUser4)) {
+      if (member.key.equals(acts.testUser4)) {
         user4Included = true;
       }
     });
     assert(user4Included);
   });
 
+  it("Transactions from a previous member set cannot be approved or executed", async () => {
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    const msigMember4 = await MultisigMember.newFromAddress(
+      acts.testUser4Keypair, multisigWallet, program, "processed");
+    // Approval by a new member
+    let err = null;
+    try {
+      await msigMember4.approveRpc(staleTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      err = e;
+    }
+    assert.isNotNull(err);
+    // Unapproval by an existing member
+    err = null;
+    try {
+      await msigMember1.unapproveRpc(staleTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      err = e;
+    }
+    assert.isNotNull(err);
+    // Execution, even though the threshold was met under the old member set
+    err = null;
+    try {
+      await msigMember1.executeRpc(staleTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      err = e;
+    }
+    assert.isNotNull(err);
+    let tx = await program.account.multisigTransaction.fetch(staleTransaction, "processed");
+    assert(tx.executedAt === null);
+  });
+
+  it("Executed and stale transactions are closed, returning rent to the proposer", async () => {
+    const conn = anchor.getProvider().connection;
+    // User3 pays the fees, so that User1's balance only reflects the refunds.
+    const msigMember3 = await MultisigMember.newFromAddress(
+      acts.testUser3Keypair, multisigWallet, program, "processed");
+    const proposerBefore = await conn.getBalance(acts.testUser1, "processed");
+    const rent = (await conn.getBalance(multisigTransaction, "processed")) +
+      (await conn.getBalance(staleTransaction, "processed"));
+    try {
+      await msigMember3.closeTransactionRpc(multisigTransaction,
+        {commitment: "processed"});
+      await msigMember3.closeTransactionRpc(staleTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    assert.isNull(await conn.getAccountInfo(multisigTransaction, "processed"));
+    assert.isNull(await conn.getAccountInfo(staleTransaction, "processed"));
+    assert((await conn.getBalance(acts.testUser1, "processed")) === proposerBefore + rent);
+  });
+
   it("Change threshold", async () => {
     const msigMember1 = await MultisigMember.newFromAddress(
       acts.testUser1Keypair, multisigWallet, program, "processed");
@@ -295,4 +445,189 @@ describe("multisig-demo", () => {
     await msigMember1.refreshWallet("processed");
     assert(msigMember1.wallet.threshold === 1);
   });
+
+  it("Change Members grows the multisig account, with the vault paying rent", async () => {
+    const conn = anchor.getProvider().connection;
+    const vault = findVaultAddress(multisigWallet, 0, program.programId);
+    const amount = 100_000_000;
+    await fundVault(vault, amount);
+    const before = await conn.getAccountInfo(multisigWallet, "processed");
+
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    let msigTransaction = msigMember1.nextTransactionAddress();
+    try {
+      await msigMember1.proposeChangeMembers(
+        [acts.testUser1, acts.testUser2, acts.testUser3, acts.testUser4],
+        {commitment: "processed"});
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await msigMember1.approveAndExecuteRpc(
+        msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    const after = await conn.getAccountInfo(multisigWallet, "processed");
+    assert(after.data.length === before.data.length + 32);
+    assert(after.lamports === await conn.getMinimumBalanceForRentExemption(after.data.length));
+    const rentPaid = after.lamports - before.lamports;
+    assert(rentPaid > 0);
+    assert((await conn.getBalance(vault, "processed")) === amount - rentPaid);
+    let wallet = await program.account.multisigWallet.fetch(multisigWallet, "processed");
+    assert(wallet.members.length === 4);
+  });
+
+  it("Change Members shrinks the multisig account, refunding rent to the vault", async () => {
+    const conn = anchor.getProvider().connection;
+    const vault = findVaultAddress(multisigWallet, 0, program.programId);
+    const before = await conn.getAccountInfo(multisigWallet, "processed");
+    const vaultBefore = await conn.getBalance(vault, "processed");
+
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    let msigTransaction = msigMember1.nextTransactionAddress();
+    try {
+      await msigMember1.proposeChangeMembers(
+        [acts.testUser1, acts.testUser2],
+        {commitment: "processed"});
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await msigMember1.approveAndExecuteRpc(
+        msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    const after = await conn.getAccountInfo(multisigWallet, "processed");
+    assert(after.data.length === before.data.length - 64);
+    assert(after.lamports === await conn.getMinimumBalanceForRentExemption(after.data.length));
+    const refund = before.lamports - after.lamports;
+    assert(refund > 0);
+    assert((await conn.getBalance(vault, "processed")) === vaultBefore + refund);
+    let wallet = await program.account.multisigWallet.fetch(multisigWallet, "processed");
+    assert(wallet.members.length === 2);
+  });
+
+  it("Enough rejections cancel a transaction", async () => {
+    // Members are now [User1, User2] with a threshold of 1,
+    // so both members must reject before the threshold is unreachable.
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    const msigMember2 = await MultisigMember.newFromAddress(
+      acts.testUser2Keypair, multisigWallet, program, "processed");
+    let msigTransaction = msigMember1.nextTransactionAddress();
+    const ix = createMemoInstruction("nobody wants this", [multisigWallet]);
+    try {
+      await msigMember1.newTransactionAndApproveRpc([ix],
+        {commitment: "processed"});
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await msigMember1.rejectRpc(msigTransaction,
+        {commitment: "processed"});
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    let tx = await program.account.multisigTransaction.fetch(msigTransaction, "processed");
+    // Rejecting withdraws the approval
+    assert(!tx.approved[0] && !tx.approved[1]);
+    assert(tx.cancelledAt === null);
+    try {
+      await msigMember2.rejectRpc(msigTransaction,
+        {commitment: "processed"});
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    tx = await program.account.multisigTransaction.fetch(msigTransaction, "processed");
+    assert(tx.cancelledAt !== null);
+    // A cancelled transaction can no longer be approved
+    let err = null;
+    try {
+      await msigMember2.approveRpc(msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      err = e;
+    }
+    assert.isNotNull(err);
+    // But it can be closed
+    await msigMember2.closeTransactionRpc(msigTransaction,
+      {commitment: "processed"});
+  });
+
+  it("Transactions cannot be executed until the timelock elapses", async () => {
+    const minDelay = 3;
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    let msigTransaction = msigMember1.nextTransactionAddress();
+    try {
+      await msigMember1.proposeChangeMinDelay(minDelay,
+        {commitment: "processed"});
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+      await msigMember1.approveAndExecuteRpc(msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await msigMember1.refreshWallet("processed");
+    assert(msigMember1.wallet.minDelay === minDelay);
+
+    // Reaching the threshold starts the timelock
+    msigTransaction = msigMember1.nextTransactionAddress();
+    const ix = createMemoInstruction("patience", [multisigWallet]);
+    await msigMember1.newTransactionAndApproveRpc([ix],
+      {commitment: "processed"});
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    let tx = await program.account.multisigTransaction.fetch(msigTransaction, "processed");
+    assert(tx.thresholdReachedAt !== null);
+    let err = null;
+    try {
+      await msigMember1.executeRpc(msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      err = e;
+    }
+    assert.isNotNull(err);
+
+    // Once the delay has elapsed, execution succeeds
+    await new Promise((resolve) => setTimeout(resolve, (minDelay + 1) * 1000));
+    try {
+      await msigMember1.executeRpc(msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+  });
+
+  it("Expired transactions cannot be approved", async () => {
+    const msigMember1 = await MultisigMember.newFromAddress(
+      acts.testUser1Keypair, multisigWallet, program, "processed");
+    let msigTransaction = msigMember1.nextTransactionAddress();
+    const ix = createMemoInstruction("short lived", [multisigWallet]);
+    try {
+      await msigMember1.newTransactionRpc([ix],
+        {commitment: "processed"}, 0, 1);
+    } catch (e) {
+      console.log(e);
+      throw e;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    let tx = await program.account.multisigTransaction.fetch(msigTransaction, "processed");
+    assert(tx.expiresAt.eq(tx.createdAt.addn(1)));
+    let err = null;
+    try {
+      await msigMember1.approveRpc(msigTransaction,
+        {commitment: "processed"});
+    } catch (e) {
+      err = e;
+    }
+    assert.isNotNull(err);
+  });
 });